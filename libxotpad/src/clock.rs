@@ -0,0 +1,134 @@
+//! Clocks.
+//!
+//! This module provides the source of time used by X.25 timers and the PAD idle
+//! timer, allowing a manual clock to be used in tests.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing_mutex::stdsync::Mutex;
+
+/// Function called when a clock is advanced, returning `false` once it is no
+/// longer required.
+pub type ClockWaker = Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// Source of time for timers.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+
+    /// Returns the time elapsed since the instant provided.
+    fn elapsed(&self, since: Instant) -> Duration {
+        self.now().saturating_duration_since(since)
+    }
+
+    /// Registers a waker, called when this clock is advanced other than by the
+    /// passing of real time, so that anything waiting on the clock can check it
+    /// again.
+    fn register_waker(&self, _waker: ClockWaker) {}
+}
+
+/// Clock that follows the system monotonic clock.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only advances when requested, for testing.
+pub struct ManualClock {
+    now: Mutex<Instant>,
+    wakers: Mutex<Vec<ClockWaker>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            now: Mutex::new(Instant::now()),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Advances the clock by the duration provided.
+    ///
+    /// Anything waiting on the clock is woken, to observe the new time.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+
+        // The wakers are called without the lock held, as they acquire the
+        // locks of the waiters.
+        let wakers = self.wakers.lock().unwrap().clone();
+
+        let expired: Vec<ClockWaker> = wakers.into_iter().filter(|waker| !waker()).collect();
+
+        self.wakers
+            .lock()
+            .unwrap()
+            .retain(|waker| !expired.iter().any(|e| Arc::ptr_eq(e, waker)));
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl fmt::Debug for ManualClock {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("ManualClock").field(&self.now()).finish()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn register_waker(&self, waker: ClockWaker) {
+        self.wakers.lock().unwrap().push(waker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new();
+
+        let start = clock.now();
+
+        assert_eq!(clock.elapsed(start), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(30));
+
+        assert_eq!(clock.elapsed(start), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn manual_clock_wakers() {
+        let clock = ManualClock::new();
+
+        let count = Arc::new(AtomicUsize::new(0));
+
+        clock.register_waker({
+            let count = Arc::clone(&count);
+
+            Arc::new(move || count.fetch_add(1, Ordering::Relaxed) == 0)
+        });
+
+        clock.advance(Duration::from_secs(1));
+        clock.advance(Duration::from_secs(1));
+
+        // The waker is removed once it is no longer required.
+        clock.advance(Duration::from_secs(1));
+
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+}
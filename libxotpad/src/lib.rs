@@ -1,4 +1,8 @@
+pub mod clock;
 pub mod pad;
+pub mod switch;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub mod x121;
 pub mod x25;
 pub mod x29;
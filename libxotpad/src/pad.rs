@@ -1,20 +1,25 @@
 use bytes::{Bytes, BytesMut};
 use std::collections::VecDeque;
 use std::io::{self, Read, Write};
-use std::ops::Add;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
+use tracing::{debug, warn};
 use tracing_mutex::stdsync::{Condvar, Mutex, RwLock};
 
+use crate::clock::Clock;
 use crate::x121::X121Addr;
-use crate::x25::{Svc, Vc, X25Params};
+use crate::x25::{Svc, SvcStats, Vc, VcEvent, X25Params};
 use crate::x29::{X29CallUserData, X29PadMessage};
-use crate::x3::{X3Echo, X3Editing, X3Forward, X3Idle, X3LfInsert, X3ParamError, X3Params};
-use crate::xot::XotLink;
+use crate::x3::{
+    X3AncillaryControl, X3Break, X3CrPadding, X3DiscardOutput, X3Echo, X3EchoMask, X3Editing,
+    X3EditingSignals, X3FlowControl, X3Forward, X3Idle, X3LfInsert, X3LfPadding, X3LineFolding,
+    X3PageWait, X3ParamError, X3Params, X3Parity, X3Recall, X3ServiceSignals, X3Speed,
+};
+use crate::xot::{XotLink, XotLinkStats};
 
 type SendQueue = (VecDeque<u8>, Option<Instant>);
 type IndicateMessage = Vec<(u8, Result<u8, X3ParamError>)>;
@@ -24,9 +29,10 @@ pub struct Pad<Q: X3Params + Send + Sync + 'static> {
     params: Arc<RwLock<PadParams<Q>>>,
     should_suppress_echo_when_editing: bool,
     send_queue: Arc<(Mutex<SendQueue>, Condvar)>,
-    recv_queue: Arc<(Mutex<VecDeque<u8>>, Condvar)>,
+    recv_queue: Arc<(Mutex<RecvQueue>, Condvar)>,
     recv_end: Arc<AtomicBool>,
     indicate_channel: Arc<Mutex<Option<Sender<IndicateMessage>>>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
@@ -36,10 +42,16 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
         should_suppress_echo_when_editing: bool,
     ) -> Self {
         let send_queue = Arc::new((Mutex::new((VecDeque::new(), None)), Condvar::new()));
-        let recv_queue = Arc::new((Mutex::new(VecDeque::new()), Condvar::new()));
+        let recv_queue = Arc::new((Mutex::new(RecvQueue::default()), Condvar::new()));
         let recv_end = Arc::new(AtomicBool::new(false));
         let indicate_channel = Arc::new(Mutex::new(None::<Sender<IndicateMessage>>));
 
+        // The idle timer uses the same clock as the circuit timers.
+        let clock = svc.clock();
+
+        // The parameters are reset to this profile by an empty set message.
+        let initial_params = params.read().unwrap().all();
+
         thread::Builder::new()
             .name("pad".to_string())
             .spawn({
@@ -51,9 +63,25 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                 let indicate_channel = Arc::clone(&indicate_channel);
 
                 move || {
+                    let _span = svc.span().clone().entered();
+
                     let mut should_clear = false;
 
                     loop {
+                        // While output is held, data is left with the circuit once
+                        // enough is buffered, so that the remote party is flow
+                        // controlled rather than buffering without limit.
+                        {
+                            let mut queue = recv_queue.0.lock().unwrap();
+
+                            while queue.is_held()
+                                && queue.data.len() >= HELD_OUTPUT_LIMIT
+                                && svc.is_connected()
+                            {
+                                queue = recv_queue.1.wait(queue).unwrap();
+                            }
+                        }
+
                         let result = svc.recv();
 
                         match result {
@@ -62,14 +90,23 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
 
                                 match message {
                                     Ok(X29PadMessage::Set(request)) => {
+                                        let message = {
+                                            let mut params = params.write().unwrap();
+
+                                            let message =
+                                                set_params(&mut *params, &initial_params, &request);
+
+                                            update_recv_queue(&recv_queue, &params);
+
+                                            message
+                                        };
+
                                         // According to the specification, a response message is only sent
                                         // if there are errors. It is not clear to me how that can be
                                         // handled by the remote party - how do they know how to long to
                                         // wait for an error response versus no response (indicating
                                         // success)?
-                                        if let Some(message) =
-                                            set_params(&mut *params.write().unwrap(), &request)
-                                        {
+                                        if let Some(message) = message {
                                             if let Err(_err) = send_message(&svc, message) {
                                                 todo!();
                                             }
@@ -84,10 +121,19 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                                         }
                                     }
                                     Ok(X29PadMessage::SetRead(request)) => {
-                                        let message = set_read_params(
-                                            &mut *params.write().unwrap(),
-                                            &request,
-                                        );
+                                        let message = {
+                                            let mut params = params.write().unwrap();
+
+                                            let message = set_read_params(
+                                                &mut *params,
+                                                &initial_params,
+                                                &request,
+                                            );
+
+                                            update_recv_queue(&recv_queue, &params);
+
+                                            message
+                                        };
 
                                         if let Err(_err) = send_message(&svc, message) {
                                             todo!();
@@ -102,6 +148,25 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                                             }
                                         }
                                     }
+                                    Ok(X29PadMessage::IndicationOfBreak(request)) => {
+                                        // There is no way to signal a break to the DTE, but the
+                                        // parameters are applied, typically 8:0 to resume output
+                                        // in response to our break.
+                                        if request.is_empty() {
+                                            debug!("X.29 indication of break ignored");
+                                            continue;
+                                        }
+
+                                        let mut params = params.write().unwrap();
+
+                                        if set_params(&mut *params, &initial_params, &request)
+                                            .is_some()
+                                        {
+                                            warn!("X.29 indication of break parameters invalid");
+                                        }
+
+                                        update_recv_queue(&recv_queue, &params);
+                                    }
                                     Ok(X29PadMessage::ClearInvitation) => {
                                         if let Err(_err) = send_queued_data(
                                             &svc,
@@ -117,9 +182,10 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                                         should_clear = true;
                                         break;
                                     }
-                                    Err(e) => {
-                                        dbg!(e);
-                                        todo!()
+                                    Err(err) => {
+                                        // TODO: respond with an X.29 error message,
+                                        // for now the message is ignored.
+                                        warn!(error = %err, "X.29 message decode error");
                                     }
                                 }
                             }
@@ -137,7 +203,8 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                                 break;
                             }
                             Err(err) => {
-                                dbg!(err); // TODO: do we need to store this somewhere
+                                // TODO: do we need to store this somewhere
+                                warn!(error = %err, "receive error");
                                 break;
                             }
                         }
@@ -157,11 +224,66 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
             })
             .expect("failed to spawn thread");
 
+        thread::Builder::new()
+            .name("pad_flow".to_string())
+            .spawn({
+                let events = svc.subscribe();
+                let params = Arc::clone(&params);
+                let recv_queue = Arc::clone(&recv_queue);
+
+                move || {
+                    for event in events {
+                        match event {
+                            VcEvent::FlowBlocked => {
+                                let params = params.read().unwrap();
+
+                                if !params.ancillary_control.in_data_transfer() {
+                                    continue;
+                                }
+
+                                recv_queue.0.lock().unwrap().stop_input();
+                            }
+                            // A reset discards the data that was blocked.
+                            VcEvent::FlowUnblocked | VcEvent::Reset { .. } => {
+                                recv_queue.0.lock().unwrap().start_input();
+                            }
+                            VcEvent::Cleared { .. } | VcEvent::OutOfOrder(_) => break,
+                            _ => continue,
+                        }
+
+                        recv_queue.1.notify_all();
+                    }
+
+                    // Wake the receiver if it is waiting for held output to be
+                    // delivered, the lock ensures the wakeup is not lost.
+                    drop(recv_queue.0.lock().unwrap());
+                    recv_queue.1.notify_all();
+                }
+            })
+            .expect("failed to spawn thread");
+
+        // The idle timer is checked again when the clock is advanced.
+        clock.register_waker({
+            let send_queue = Arc::downgrade(&send_queue);
+
+            Arc::new(move || {
+                let Some(send_queue) = send_queue.upgrade() else {
+                    return false;
+                };
+
+                let _queue = send_queue.0.lock().unwrap();
+                send_queue.1.notify_all();
+
+                true
+            })
+        });
+
         thread::Builder::new()
             .name("pad_send_idle".to_string())
             .spawn({
                 let svc = svc.clone();
                 let send_queue = Arc::clone(&send_queue);
+                let clock = Arc::clone(&clock);
 
                 move || {
                     let mut queue = send_queue.0.lock().unwrap();
@@ -170,7 +292,7 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                         // If the deadline has expired, send the queued data.
                         if let Some(deadline) = queue.1 {
                             #[allow(clippy::collapsible_if)]
-                            if !queue.0.is_empty() && Instant::now() >= deadline {
+                            if !queue.0.is_empty() && clock.now() >= deadline {
                                 if send_queued_data(&svc, &mut queue).is_err() {
                                     break;
                                 }
@@ -178,7 +300,7 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
                         }
 
                         let timeout = queue.1.map_or(Duration::from_secs(10), |d| {
-                            d.saturating_duration_since(Instant::now())
+                            d.saturating_duration_since(clock.now())
                         });
 
                         (queue, _) = send_queue.1.wait_timeout(queue, timeout).unwrap();
@@ -195,15 +317,18 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
             recv_queue,
             recv_end,
             indicate_channel,
+            clock,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn call(
         link: XotLink,
         channel: u16,
         addr: &X121Addr,
         call_data: &[u8],
         x25_params: &X25Params,
+        clock: Arc<dyn Clock>,
         pad_params: Arc<RwLock<PadParams<Q>>>,
         should_suppress_echo_when_editing: bool,
     ) -> io::Result<Self> {
@@ -214,7 +339,7 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
 
         call_user_data.encode(&mut call_user_data_buf);
 
-        let svc = Svc::call(link, channel, addr, &call_user_data_buf, x25_params)?;
+        let svc = Svc::call(link, channel, addr, &call_user_data_buf, x25_params, clock)?;
 
         Ok(Pad::new(svc, pad_params, should_suppress_echo_when_editing))
     }
@@ -227,6 +352,17 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
         self.svc.is_connected()
     }
 
+    /// Returns the statistics for the underlying circuit, and XOT link.
+    pub fn stats(&self) -> (SvcStats, XotLinkStats) {
+        (self.svc.stats(), self.svc.link_stats())
+    }
+
+    /// Subscribes to events on the underlying circuit, such as a reset by the
+    /// remote party.
+    pub fn subscribe(&self) -> Receiver<VcEvent> {
+        self.svc.subscribe()
+    }
+
     pub fn clear(self, cause_code: u8, diagnostic_code: u8) -> io::Result<()> {
         self.svc.clear(cause_code, diagnostic_code)
     }
@@ -235,6 +371,59 @@ impl<Q: X3Params + Send + Sync + 'static> Pad<Q> {
         send_message(&self.svc, X29PadMessage::ClearInvitation)
     }
 
+    /// Handles a _break_ from the DTE, according to X.3 parameter 7.
+    ///
+    /// Returns `true` if the DTE should escape to the command state, which is
+    /// the responsibility of the caller.
+    pub fn send_break(&self) -> io::Result<bool> {
+        let on_break = self.params.read().unwrap().on_break;
+
+        if on_break.discard_output() {
+            self.params.write().unwrap().discard_output = X3DiscardOutput::try_from(1).unwrap();
+
+            self.recv_queue.0.lock().unwrap().data.clear();
+            self.recv_queue.1.notify_all();
+        }
+
+        if on_break.interrupt() {
+            match self.svc.interrupt(&[0x01]) {
+                Ok(()) => {}
+                // The previous break has not been confirmed yet.
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        if on_break.reset() {
+            match self.svc.reset(0, 0) {
+                Ok(()) => {}
+                // The previous break has not been confirmed yet.
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        // The remote party is told that output is being discarded, so that it
+        // can resume output by setting parameter 8 once it has responded.
+        if on_break.indicate() {
+            let params = if on_break.discard_output() {
+                vec![(8, 1)]
+            } else {
+                vec![]
+            };
+
+            send_message(&self.svc, X29PadMessage::IndicationOfBreak(params))?;
+        }
+
+        Ok(on_break.escape())
+    }
+
+    /// Applies a change to the parameters made by the DTE, for example using
+    /// the X.28 set command, while the call is in progress.
+    pub fn params_changed(&self) {
+        update_recv_queue(&self.recv_queue, &self.params.read().unwrap());
+    }
+
     pub fn get_remote_params(&self, request: &[u8]) -> io::Result<Vec<(u8, Option<u8>)>> {
         let response = send_message_recv_indicate(
             &self.svc,
@@ -283,6 +472,7 @@ impl<Q: X3Params + Send + Sync + 'static> Clone for Pad<Q> {
             recv_queue: Arc::clone(&self.recv_queue),
             recv_end: Arc::clone(&self.recv_end),
             indicate_channel: Arc::clone(&self.indicate_channel),
+            clock: Arc::clone(&self.clock),
         }
     }
 }
@@ -296,26 +486,40 @@ impl<Q: X3Params + Send + Sync + 'static> Read for Pad<Q> {
         let mut queue = self.recv_queue.0.lock().unwrap();
 
         loop {
+            // We won't miss any data as the queue is locked.
+            let recv_end = self.recv_end.load(Ordering::Relaxed);
+
             let mut index = 0;
 
-            while index < buf.len() {
-                let Some(byte) = queue.pop_front() else {
+            // Flow control characters are sent ahead of any data, even if the
+            // output is stopped.
+            if let Some(byte) = queue.flow_control.take() {
+                buf[index] = byte;
+
+                index += 1;
+            }
+
+            // Once the call has ended there is nothing to wait for, so the page
+            // wait and X-OFF conditions are ignored.
+            while index < buf.len() && (!queue.is_held() || recv_end) {
+                let Some(byte) = queue.data.pop_front() else {
                     break;
                 };
 
                 buf[index] = byte;
 
                 index += 1;
+
+                if byte & 0x7f == /* LF */ 0x0a {
+                    queue.page_line_feed();
+                }
             }
 
             if index > 0 {
                 return Ok(index);
             }
 
-            assert!(queue.is_empty());
-
-            // We won't miss any data as the queue is locked.
-            if self.recv_end.load(Ordering::Relaxed) {
+            if recv_end && queue.data.is_empty() {
                 return Ok(0);
             }
 
@@ -346,17 +550,50 @@ impl<Q: X3Params + Send + Sync + 'static> Write for Pad<Q> {
 
             let idle: Option<Duration> = params.idle.into();
 
-            let send_deadline = idle.map(|d| Instant::now().add(d));
+            let send_deadline = idle.map(|d| self.clock.now() + d);
 
             let mut send_queue = self.send_queue.0.lock().unwrap();
             let mut recv_queue = self.recv_queue.0.lock().unwrap(); // because of echo...
 
+            if recv_queue.update_params(&params) {
+                should_wake_up_recv = true;
+            }
+
             for &byte in buf {
-                if should_echo {
-                    recv_queue.push_back(byte);
+                count += 1;
+
+                // Any character from the DTE ends the page wait condition, it
+                // is not forwarded.
+                if recv_queue.is_page_wait {
+                    recv_queue.resume_page();
+
+                    should_wake_up_recv = true;
+                    continue;
+                }
+
+                let Some(byte) = params.parity.check(byte) else {
+                    continue;
+                };
+
+                if params.flow_control.into() {
+                    if byte == /* X-OFF */ 0x13 {
+                        recv_queue.is_output_stopped = true;
+                        continue;
+                    }
+
+                    if byte == /* X-ON */ 0x11 {
+                        recv_queue.is_output_stopped = false;
+
+                        should_wake_up_recv = true;
+                        continue;
+                    }
+                }
+
+                if should_echo && !params.echo_mask.is_match(byte) {
+                    recv_queue.push(byte, &params);
 
                     if params.lf_insert.after_echo(byte) {
-                        recv_queue.push_back(/* LF */ 0x0a);
+                        recv_queue.push(/* LF */ 0x0a, &params);
                     }
 
                     should_wake_up_recv = true;
@@ -371,8 +608,6 @@ impl<Q: X3Params + Send + Sync + 'static> Write for Pad<Q> {
                 if params.forward.is_match(byte) || send_queue.0.len() >= packet_size {
                     send_queued_data(&self.svc, &mut send_queue)?;
                 }
-
-                count += 1;
             }
 
             if !send_queue.0.is_empty() {
@@ -407,12 +642,150 @@ impl<Q: X3Params + Send + Sync + 'static> Write for Pad<Q> {
     }
 }
 
-fn queue_recv_data<Q: X3Params>(queue: &mut VecDeque<u8>, data: Bytes, params: &PadParams<Q>) {
+/// The number of bytes held for the DTE, by page wait or X-OFF, before no more
+/// data is received from the circuit.
+const HELD_OUTPUT_LIMIT: usize = 4096;
+
+/// Data for the DTE, both received and echoed.
+#[derive(Default)]
+struct RecvQueue {
+    data: VecDeque<u8>,
+    column: usize,
+    page_wait: Option<usize>,
+    page_lines: usize,
+    is_page_wait: bool,
+    is_output_stopped: bool,
+    is_input_stopped: bool,
+    flow_control: Option<u8>,
+}
+
+impl RecvQueue {
+    fn push<Q: X3Params>(&mut self, byte: u8, params: &PadParams<Q>) {
+        let line_folding: Option<usize> = params.line_folding.into();
+
+        let is_printable = (0x20..0x7f).contains(&byte);
+
+        if let Some(width) = line_folding {
+            if is_printable && self.column >= width {
+                self.push_padded(/* CR */ 0x0d, params);
+                self.push_padded(/* LF */ 0x0a, params);
+            }
+        }
+
+        match byte {
+            /* BS */ 0x08 => self.column = self.column.saturating_sub(1),
+            _ if is_printable => self.column += 1,
+            _ => {}
+        }
+
+        self.push_padded(byte, params);
+    }
+
+    fn push_padded<Q: X3Params>(&mut self, byte: u8, params: &PadParams<Q>) {
+        if byte == /* CR */ 0x0d {
+            self.column = 0;
+        }
+
+        self.data.push_back(params.parity.generate(byte));
+
+        for _ in 0..params.padding(byte) {
+            self.data.push_back(/* NUL */ 0x00);
+        }
+    }
+
+    fn page_line_feed(&mut self) {
+        let Some(page_wait) = self.page_wait else {
+            return;
+        };
+
+        self.page_lines += 1;
+
+        if self.page_lines >= page_wait {
+            self.is_page_wait = true;
+        }
+    }
+
+    fn resume_page(&mut self) {
+        self.page_lines = 0;
+        self.is_page_wait = false;
+    }
+
+    fn is_held(&self) -> bool {
+        self.is_page_wait || self.is_output_stopped
+    }
+
+    /// Updates the output conditions for the parameters provided, returning
+    /// `true` if output that was held can now be delivered.
+    fn update_params<Q: X3Params>(&mut self, params: &PadParams<Q>) -> bool {
+        self.page_wait = params.page_wait.into();
+
+        let mut is_released = false;
+
+        // Once disabled, nothing would end the page wait or X-OFF condition.
+        if self.is_page_wait && self.page_wait.is_none() {
+            self.resume_page();
+
+            is_released = true;
+        }
+
+        if self.is_output_stopped && !bool::from(params.flow_control) {
+            self.is_output_stopped = false;
+
+            is_released = true;
+        }
+
+        is_released
+    }
+
+    /// Sends X-OFF to the DTE, unless it has already been sent.
+    fn stop_input(&mut self) {
+        if self.is_input_stopped {
+            return;
+        }
+
+        self.is_input_stopped = true;
+
+        // An X-ON that has not been sent yet is cancelled out.
+        self.flow_control = match self.flow_control {
+            Some(/* X-ON */ 0x11) => None,
+            _ => Some(/* X-OFF */ 0x13),
+        };
+    }
+
+    /// Sends X-ON to the DTE, if X-OFF has been sent.
+    fn start_input(&mut self) {
+        if !self.is_input_stopped {
+            return;
+        }
+
+        self.is_input_stopped = false;
+
+        // An X-OFF that has not been sent yet is cancelled out.
+        self.flow_control = match self.flow_control {
+            Some(/* X-OFF */ 0x13) => None,
+            _ => Some(/* X-ON */ 0x11),
+        };
+    }
+}
+
+fn update_recv_queue<Q: X3Params>(recv_queue: &(Mutex<RecvQueue>, Condvar), params: &PadParams<Q>) {
+    if recv_queue.0.lock().unwrap().update_params(params) {
+        recv_queue.1.notify_all();
+    }
+}
+
+fn queue_recv_data<Q: X3Params>(queue: &mut RecvQueue, data: Bytes, params: &PadParams<Q>) {
+    if params.discard_output.into() {
+        return;
+    }
+
+    queue.update_params(params);
+
     for byte in data {
-        queue.push_back(byte);
+        queue.push(byte, params);
 
         if params.lf_insert.after_recv(byte) {
-            queue.push_back(/* LF */ 0x0a);
+            queue.push(/* LF */ 0x0a, params);
         }
     }
 }
@@ -474,10 +847,14 @@ fn read_params<Q: X3Params>(params: &Q, request: &[u8]) -> X29PadMessage {
     X29PadMessage::Indicate(response)
 }
 
-fn set_params<Q: X3Params>(params: &mut Q, request: &[(u8, u8)]) -> Option<X29PadMessage> {
+fn set_params<Q: X3Params>(
+    params: &mut Q,
+    initial_params: &[(u8, u8)],
+    request: &[(u8, u8)],
+) -> Option<X29PadMessage> {
     if request.is_empty() {
-        // TODO: how do we reset the params here?
-        todo!();
+        reset_params(params, initial_params);
+        return None;
     }
 
     let response: Vec<(u8, Result<u8, X3ParamError>)> = request
@@ -499,10 +876,14 @@ fn set_params<Q: X3Params>(params: &mut Q, request: &[(u8, u8)]) -> Option<X29Pa
     Some(X29PadMessage::Indicate(response))
 }
 
-fn set_read_params<Q: X3Params>(params: &mut Q, request: &[(u8, u8)]) -> X29PadMessage {
+fn set_read_params<Q: X3Params>(
+    params: &mut Q,
+    initial_params: &[(u8, u8)],
+    request: &[(u8, u8)],
+) -> X29PadMessage {
     if request.is_empty() {
-        // TODO: how do we reset the params here?
-        todo!();
+        reset_params(params, initial_params);
+        return read_params(params, &[]);
     }
 
     let response: Vec<(u8, Result<u8, X3ParamError>)> = request
@@ -520,31 +901,95 @@ fn set_read_params<Q: X3Params>(params: &mut Q, request: &[(u8, u8)]) -> X29PadM
     X29PadMessage::Indicate(response)
 }
 
-const PARAMS: [u8; 5] = [2, 3, 4, 13, 15];
+fn reset_params<Q: X3Params>(params: &mut Q, initial_params: &[(u8, u8)]) {
+    for &(param, value) in initial_params {
+        // The initial values were read from the same parameters, so they are
+        // all valid.
+        let _ = params.set(param, value);
+    }
+}
+
+const PARAMS: [u8; 19] = [
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 20, 21, 22,
+];
 
 #[derive(Clone, Debug)]
 pub struct PadParams<Q: X3Params> {
+    pub recall: X3Recall,
+
     pub echo: X3Echo,
 
     pub forward: X3Forward,
 
     pub idle: X3Idle,
 
+    pub ancillary_control: X3AncillaryControl,
+
+    pub service_signals: X3ServiceSignals,
+
+    pub on_break: X3Break,
+
+    pub discard_output: X3DiscardOutput,
+
+    pub cr_padding: X3CrPadding,
+
+    pub line_folding: X3LineFolding,
+
+    pub speed: X3Speed,
+
+    pub flow_control: X3FlowControl,
+
     pub lf_insert: X3LfInsert,
 
+    pub lf_padding: X3LfPadding,
+
     pub editing: X3Editing,
 
+    pub editing_signals: X3EditingSignals,
+
+    pub echo_mask: X3EchoMask,
+
+    pub parity: X3Parity,
+
+    pub page_wait: X3PageWait,
+
     pub delegate: Option<Q>,
 }
 
+impl<Q: X3Params> PadParams<Q> {
+    /// Returns the number of padding characters to send to the DTE after the
+    /// byte, according to X.3 parameters 9 and 14.
+    pub fn padding(&self, byte: u8) -> usize {
+        match byte {
+            /* CR */ 0x0d => usize::from(*self.cr_padding),
+            /* LF */ 0x0a => usize::from(*self.lf_padding),
+            _ => 0,
+        }
+    }
+}
+
 impl<Q: X3Params> X3Params for PadParams<Q> {
     fn get(&self, param: u8) -> Option<u8> {
         match (param, &self.delegate) {
+            (1, _) => Some(*self.recall),
             (2, _) => Some(*self.echo),
             (3, _) => Some(*self.forward),
             (4, _) => Some(*self.idle),
+            (5, _) => Some(*self.ancillary_control),
+            (6, _) => Some(*self.service_signals),
+            (7, _) => Some(*self.on_break),
+            (8, _) => Some(*self.discard_output),
+            (9, _) => Some(*self.cr_padding),
+            (10, _) => Some(*self.line_folding),
+            (11, _) => Some(*self.speed),
+            (12, _) => Some(*self.flow_control),
             (13, _) => Some(*self.lf_insert),
+            (14, _) => Some(*self.lf_padding),
             (15, _) => Some(*self.editing),
+            (19, _) => Some(*self.editing_signals),
+            (20, _) => Some(*self.echo_mask),
+            (21, _) => Some(*self.parity),
+            (22, _) => Some(*self.page_wait),
             (_, Some(ref delegate)) => delegate.get(param),
             (_, None) => None,
         }
@@ -552,11 +997,27 @@ impl<Q: X3Params> X3Params for PadParams<Q> {
 
     fn set(&mut self, param: u8, value: u8) -> Result<(), X3ParamError> {
         match (param, &mut self.delegate) {
+            (1, _) => self.recall = X3Recall::try_from(value)?,
             (2, _) => self.echo = X3Echo::try_from(value)?,
             (3, _) => self.forward = X3Forward::try_from(value)?,
             (4, _) => self.idle = X3Idle::from(value),
+            (5, _) => self.ancillary_control = X3AncillaryControl::try_from(value)?,
+            (6, _) => self.service_signals = X3ServiceSignals::try_from(value)?,
+            (7, _) => self.on_break = X3Break::try_from(value)?,
+            (8, _) => self.discard_output = X3DiscardOutput::try_from(value)?,
+            (9, _) => self.cr_padding = X3CrPadding::from(value),
+            (10, _) => self.line_folding = X3LineFolding::from(value),
+            // The speed is read only, it is a property of the DTE.
+            (11, _) if value == *self.speed => {}
+            (11, _) => return Err(X3ParamError::InvalidValue),
+            (12, _) => self.flow_control = X3FlowControl::try_from(value)?,
             (13, _) => self.lf_insert = X3LfInsert::try_from(value)?,
+            (14, _) => self.lf_padding = X3LfPadding::from(value),
             (15, _) => self.editing = X3Editing::try_from(value)?,
+            (19, _) => self.editing_signals = X3EditingSignals::try_from(value)?,
+            (20, _) => self.echo_mask = X3EchoMask::from(value),
+            (21, _) => self.parity = X3Parity::try_from(value)?,
+            (22, _) => self.page_wait = X3PageWait::from(value),
             (_, Some(ref mut delegate)) => delegate.set(param, value)?,
             (_, None) => return Err(X3ParamError::Unsupported),
         };
@@ -580,3 +1041,578 @@ impl<Q: X3Params> X3Params for PadParams<Q> {
         params
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use crate::clock::ManualClock;
+    use crate::test_util::{peer_pair, ScriptedPeer};
+    use crate::x25::packet::{
+        X25CallAccept, X25Data, X25Packet, X25ReceiveNotReady, X25ReceiveReady, X25ResetConfirm,
+    };
+    use crate::x25::{X25AddrFormat, X25Modulo, X25Role};
+
+    #[derive(Clone, Debug)]
+    struct NoParams;
+
+    impl X3Params for NoParams {
+        fn get(&self, _param: u8) -> Option<u8> {
+            None
+        }
+
+        fn set(&mut self, _param: u8, _value: u8) -> Result<(), X3ParamError> {
+            Err(X3ParamError::Unsupported)
+        }
+
+        fn all(&self) -> Vec<(u8, u8)> {
+            vec![]
+        }
+    }
+
+    fn x25_params() -> X25Params {
+        X25Params {
+            addr: X121Addr::from_str("1111").unwrap(),
+            modulo: X25Modulo::Normal,
+            send_packet_size: 128,
+            send_window_size: 2,
+            recv_packet_size: 128,
+            recv_window_size: 2,
+            max_recv_sequence_len: None,
+            t21: Duration::from_secs(5),
+            t22: Duration::from_secs(5),
+            t23: Duration::from_secs(5),
+            addr_format: X25AddrFormat::NonToaNpi,
+            idle_probe: None,
+            role: X25Role::Dte,
+            channels: 1..=1024,
+        }
+    }
+
+    fn pad_params(idle: u8) -> PadParams<NoParams> {
+        PadParams {
+            recall: X3Recall::try_from(0).unwrap(),
+            echo: X3Echo::try_from(0).unwrap(),
+            forward: X3Forward::try_from(0).unwrap(),
+            idle: X3Idle::from(idle),
+            ancillary_control: X3AncillaryControl::try_from(0).unwrap(),
+            service_signals: X3ServiceSignals::try_from(1).unwrap(),
+            on_break: X3Break::try_from(0).unwrap(),
+            discard_output: X3DiscardOutput::try_from(0).unwrap(),
+            cr_padding: X3CrPadding::from(0),
+            line_folding: X3LineFolding::from(0),
+            speed: X3Speed::try_from(14).unwrap(),
+            flow_control: X3FlowControl::try_from(0).unwrap(),
+            lf_insert: X3LfInsert::try_from(0).unwrap(),
+            lf_padding: X3LfPadding::from(0),
+            editing: X3Editing::try_from(0).unwrap(),
+            editing_signals: X3EditingSignals::try_from(0).unwrap(),
+            echo_mask: X3EchoMask::from(0),
+            parity: X3Parity::try_from(0).unwrap(),
+            page_wait: X3PageWait::from(0),
+            delegate: None,
+        }
+    }
+
+    fn call(
+        clock: &Arc<ManualClock>,
+        pad_params: PadParams<NoParams>,
+    ) -> (Pad<NoParams>, ScriptedPeer) {
+        let (link, mut peer) = peer_pair().unwrap();
+
+        let call = thread::spawn({
+            let clock = Arc::clone(clock);
+
+            move || {
+                let addr = X121Addr::from_str("2222").unwrap();
+                let pad_params = Arc::new(RwLock::new(pad_params));
+
+                Pad::call(link, 1, &addr, b"", &x25_params(), clock, pad_params, false)
+            }
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallRequest(_)));
+
+        let call_accept = X25CallAccept {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            called_addr: X121Addr::null(),
+            calling_addr: X121Addr::null(),
+            facilities: vec![],
+            called_user_data: Bytes::new(),
+        };
+
+        peer.send(call_accept).unwrap();
+
+        (call.join().unwrap().unwrap(), peer)
+    }
+
+    fn data(send_seq: u8, user_data: &'static [u8]) -> X25Data {
+        X25Data {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            send_seq,
+            recv_seq: 0,
+            qualifier: false,
+            delivery: false,
+            more: false,
+            user_data: Bytes::from_static(user_data),
+        }
+    }
+
+    fn message(send_seq: u8, message: X29PadMessage) -> X25Data {
+        let mut buf = BytesMut::new();
+
+        message.encode(&mut buf);
+
+        X25Data {
+            qualifier: true,
+            user_data: buf.freeze(),
+            ..data(send_seq, b"")
+        }
+    }
+
+    #[test]
+    fn idle_forwarding() {
+        let clock = Arc::new(ManualClock::new());
+
+        let (mut pad, mut peer) = call(&clock, pad_params(20)); // 1 second
+
+        pad.write_all(b"hello").unwrap();
+
+        clock.advance(Duration::from_secs(2));
+
+        let X25Packet::Data(data) = peer.recv().unwrap() else {
+            panic!("expected data packet");
+        };
+
+        assert_eq!(&data.user_data[..], b"hello");
+    }
+
+    #[test]
+    fn params_get_set() {
+        let mut params = pad_params(0);
+
+        assert_eq!(params.set(1, 1), Ok(()));
+        assert_eq!(params.set(1, 20), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.set(6, 5), Ok(()));
+        assert_eq!(params.set(6, 2), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.set(7, 21), Ok(()));
+        assert_eq!(params.set(7, 32), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.set(10, 80), Ok(()));
+        assert_eq!(params.set(19, 2), Ok(()));
+        assert_eq!(params.set(19, 3), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.set(21, 4), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.set(16, 127), Err(X3ParamError::Unsupported));
+
+        assert_eq!(params.get(1), Some(1));
+        assert_eq!(params.get(6), Some(5));
+        assert_eq!(params.get(7), Some(21));
+        assert_eq!(params.get(10), Some(80));
+        assert_eq!(params.get(19), Some(2));
+        assert_eq!(params.get(16), None);
+
+        assert_eq!(params.all().len(), 19);
+    }
+
+    #[test]
+    fn params_speed_read_only() {
+        let mut params = pad_params(0);
+
+        assert_eq!(params.set(11, 14), Ok(()));
+        assert_eq!(params.set(11, 12), Err(X3ParamError::InvalidValue));
+        assert_eq!(params.get(11), Some(14));
+    }
+
+    #[test]
+    fn set_params_reset() {
+        let mut params = pad_params(0);
+        let initial_params = params.all();
+
+        let response = set_params(&mut params, &initial_params, &[(2, 1), (4, 5)]);
+
+        assert!(response.is_none());
+        assert_eq!(params.get(2), Some(1));
+
+        let response = set_params(&mut params, &initial_params, &[]);
+
+        assert!(response.is_none());
+        assert_eq!(params.all(), initial_params);
+    }
+
+    #[test]
+    fn set_read_params_reset() {
+        let mut params = pad_params(0);
+        let initial_params = params.all();
+
+        set_params(&mut params, &initial_params, &[(2, 1), (4, 5)]);
+
+        let X29PadMessage::Indicate(response) = set_read_params(&mut params, &initial_params, &[])
+        else {
+            panic!("expected indicate message");
+        };
+
+        assert_eq!(params.all(), initial_params);
+
+        let expected: Vec<_> = initial_params.iter().map(|&(p, v)| (p, Ok(v))).collect();
+
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn line_folding() {
+        let mut params = pad_params(0);
+
+        params.line_folding = X3LineFolding::from(4);
+
+        let mut queue = RecvQueue::default();
+
+        queue_recv_data(&mut queue, Bytes::from_static(b"abcd\r\nabcdef"), &params);
+
+        assert_eq!(queue.data, b"abcd\r\nabcd\r\nef");
+    }
+
+    #[test]
+    fn padding() {
+        let mut params = pad_params(0);
+
+        params.cr_padding = X3CrPadding::from(2);
+        params.lf_padding = X3LfPadding::from(1);
+        params.lf_insert = X3LfInsert::try_from(1).unwrap();
+
+        let mut queue = RecvQueue::default();
+
+        // The inserted LF is padded too.
+        queue_recv_data(&mut queue, Bytes::from_static(b"a\rb\n"), &params);
+
+        assert_eq!(queue.data, b"a\r\0\0\n\0b\n\0");
+    }
+
+    #[test]
+    fn padding_line_folding() {
+        let mut params = pad_params(0);
+
+        params.cr_padding = X3CrPadding::from(1);
+        params.line_folding = X3LineFolding::from(2);
+
+        let mut queue = RecvQueue::default();
+
+        queue_recv_data(&mut queue, Bytes::from_static(b"abc"), &params);
+
+        assert_eq!(queue.data, b"ab\r\0\nc");
+    }
+
+    #[test]
+    fn discard_output() {
+        let mut params = pad_params(0);
+
+        params.discard_output = X3DiscardOutput::try_from(1).unwrap();
+
+        let mut queue = RecvQueue::default();
+
+        queue_recv_data(&mut queue, Bytes::from_static(b"hello"), &params);
+
+        assert!(queue.data.is_empty());
+    }
+
+    #[test]
+    fn page_wait() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.page_wait = X3PageWait::from(2);
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        peer.send(data(0, b"one\r\ntwo\r\nthree\r\n")).unwrap();
+
+        let mut buf = [0; 64];
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], b"one\r\ntwo\r\n");
+
+        // The character that ends the page wait is not forwarded.
+        pad.write_all(b" ").unwrap();
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], b"three\r\n");
+    }
+
+    #[test]
+    fn flow_control_output() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.flow_control = X3FlowControl::try_from(1).unwrap();
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        pad.write_all(&[/* X-OFF */ 0x13]).unwrap();
+
+        peer.send(data(0, b"hello")).unwrap();
+
+        let (tx, rx) = channel();
+
+        thread::spawn({
+            let mut pad = pad.clone();
+
+            move || {
+                let mut buf = [0; 64];
+
+                let n = pad.read(&mut buf).unwrap();
+
+                tx.send(buf[..n].to_vec()).unwrap();
+            }
+        });
+
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        pad.write_all(&[/* X-ON */ 0x11]).unwrap();
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn flow_control_output_disabled() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.flow_control = X3FlowControl::try_from(1).unwrap();
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        pad.write_all(&[/* X-OFF */ 0x13]).unwrap();
+
+        peer.send(data(0, b"hello")).unwrap();
+
+        // The remote party disables X-ON and X-OFF, while output is stopped.
+        peer.send(message(1, X29PadMessage::Set(vec![(12, 0)])))
+            .unwrap();
+
+        let mut buf = [0; 64];
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], b"hello");
+    }
+
+    #[test]
+    fn page_wait_disabled() {
+        let mut params = pad_params(0);
+
+        params.page_wait = X3PageWait::from(1);
+
+        let mut queue = RecvQueue::default();
+
+        queue.update_params(&params);
+        queue.page_line_feed();
+
+        assert!(queue.is_held());
+
+        params.page_wait = X3PageWait::from(0);
+
+        assert!(queue.update_params(&params));
+        assert!(!queue.is_held());
+    }
+
+    #[test]
+    fn flow_control_output_held() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.flow_control = X3FlowControl::try_from(1).unwrap();
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        pad.write_all(&[/* X-OFF */ 0x13]).unwrap();
+
+        // Enough to fill both the held output, and the circuit.
+        let count = HELD_OUTPUT_LIMIT / 128 + 32;
+
+        for send_seq in 0..count {
+            peer.send(data((send_seq % 8) as u8, &[b'x'; 128])).unwrap();
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while pad.stats().0.rnr_sent == 0 {
+            assert!(
+                Instant::now() < deadline,
+                "remote party not flow controlled"
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        pad.write_all(&[/* X-ON */ 0x11]).unwrap();
+
+        let mut buf = vec![0; count * 128];
+
+        pad.read_exact(&mut buf).unwrap();
+
+        assert!(buf.iter().all(|&b| b == b'x'));
+    }
+
+    #[test]
+    fn flow_control_input() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.forward = X3Forward::try_from(2).unwrap(); // CR
+        params.ancillary_control = X3AncillaryControl::try_from(1).unwrap();
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        let receive_not_ready = X25ReceiveNotReady {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            recv_seq: 0,
+        };
+
+        peer.send(receive_not_ready).unwrap();
+
+        // Wait for the remote busy condition to be processed, before sending.
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while pad.stats().0.rnr_received == 0 {
+            assert!(Instant::now() < deadline, "remote busy not received");
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        pad.write_all(b"hello\r").unwrap();
+
+        let mut buf = [0; 64];
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], &[/* X-OFF */ 0x13]);
+
+        let receive_ready = X25ReceiveReady {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            recv_seq: 0,
+        };
+
+        peer.send(receive_ready).unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::Data(_)));
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], &[/* X-ON */ 0x11]);
+    }
+
+    #[test]
+    fn flow_control_input_cancelled() {
+        let mut queue = RecvQueue::default();
+
+        queue.stop_input();
+        queue.start_input();
+
+        assert_eq!(queue.flow_control, None);
+
+        queue.stop_input();
+        queue.flow_control.take();
+        queue.start_input();
+
+        assert_eq!(queue.flow_control, Some(/* X-ON */ 0x11));
+    }
+
+    #[test]
+    fn send_break() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        // Interrupt, indication of break and discard output.
+        params.on_break = X3Break::try_from(21).unwrap();
+
+        let (mut pad, mut peer) = call(&clock, params);
+
+        assert!(!pad.send_break().unwrap());
+
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::Interrupt(interrupt) if interrupt.user_data[..] == b"\x01"[..]
+        ));
+
+        let X25Packet::Data(indication_of_break) = peer.recv().unwrap() else {
+            panic!("expected data packet");
+        };
+
+        assert!(indication_of_break.qualifier);
+        assert_eq!(&indication_of_break.user_data[..], b"\x03\x08\x01");
+
+        // Output is discarded until parameter 8 is set by the remote party...
+        peer.send(data(0, b"discarded")).unwrap();
+        peer.send(message(1, X29PadMessage::Set(vec![(8, 0)])))
+            .unwrap();
+        peer.send(data(2, b"hello")).unwrap();
+
+        let mut buf = [0; 64];
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], b"hello");
+
+        assert!(!pad.send_break().unwrap());
+
+        // The interrupt has not been confirmed, so only the indication of break
+        // is sent, after the acknowledgements of the data above.
+        while !matches!(peer.recv().unwrap(), X25Packet::Data(_)) {}
+
+        // ...or by an indication of break in response.
+        peer.send(data(3, b"discarded")).unwrap();
+        peer.send(message(4, X29PadMessage::IndicationOfBreak(vec![(8, 0)])))
+            .unwrap();
+        peer.send(data(5, b"world")).unwrap();
+
+        let n = pad.read(&mut buf).unwrap();
+
+        assert_eq!(&buf[..n], b"world");
+    }
+
+    #[test]
+    fn send_break_reset() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.on_break = X3Break::try_from(2).unwrap();
+
+        let (pad, mut peer) = call(&clock, params);
+
+        // The first break waits for the reset to be confirmed...
+        let first_break = thread::spawn({
+            let pad = pad.clone();
+
+            move || pad.send_break()
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ResetRequest(_)));
+
+        // ...so a second break does not reset again.
+        assert!(!pad.send_break().unwrap());
+
+        peer.send(X25ResetConfirm {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+        })
+        .unwrap();
+
+        assert!(!first_break.join().unwrap().unwrap());
+    }
+
+    #[test]
+    fn send_break_escape() {
+        let clock = Arc::new(ManualClock::new());
+        let mut params = pad_params(0);
+
+        params.on_break = X3Break::try_from(8).unwrap();
+
+        let (pad, _peer) = call(&clock, params);
+
+        assert!(pad.send_break().unwrap());
+    }
+}
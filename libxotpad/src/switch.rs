@@ -0,0 +1,300 @@
+//! X.25 switch.
+//!
+//! This module provides a simple switch that routes calls between XOT links, by
+//! called X.121 address, allowing multiple parties to be tested together without
+//! real routers.
+//!
+//! Each XOT link carries a single call, so the switch relays packets between the
+//! two legs of a call unchanged; flow control, resets, interrupts and clears are
+//! end-to-end.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use tracing::{debug, info, info_span, warn, Span};
+
+use crate::x121::X121Addr;
+use crate::x25::packet::{X25ClearRequest, X25Packet};
+use crate::xot::{XotLink, XotResolver};
+
+/// X.25 switch, routing calls between XOT links.
+#[derive(Debug)]
+pub struct X25Switch {
+    resolver: XotResolver,
+}
+
+impl X25Switch {
+    /// Creates a new `X25Switch` that routes calls using the resolver provided.
+    ///
+    /// A resolver rule may route to a socket address, including the port, as
+    /// well as to an XOT gateway.
+    pub fn new(resolver: XotResolver) -> Self {
+        X25Switch { resolver }
+    }
+
+    /// Accepts XOT connections from the listener, handling the call on each on a
+    /// new thread.
+    pub fn serve(self: &Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for tcp_stream in listener.incoming() {
+            let tcp_stream = match tcp_stream {
+                Ok(tcp_stream) => tcp_stream,
+                Err(err) => {
+                    warn!(error = %err, "unable to accept XOT connection");
+                    continue;
+                }
+            };
+
+            thread::Builder::new()
+                .name("x25_switch".to_string())
+                .spawn({
+                    let switch = Arc::clone(self);
+
+                    move || {
+                        if let Err(err) = switch.handle(XotLink::new(tcp_stream)) {
+                            warn!(error = %err, "switch call failed");
+                        }
+                    }
+                })
+                .expect("failed to spawn thread");
+        }
+
+        Ok(())
+    }
+
+    /// Handles the call on an incoming XOT link, returning when the call is
+    /// complete.
+    pub fn handle(&self, mut link: XotLink) -> io::Result<()> {
+        let buf = link.recv()?;
+
+        let packet = X25Packet::decode(buf.clone())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let X25Packet::CallRequest(call_request) = packet else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected call request",
+            ));
+        };
+
+        let span = info_span!(
+            parent: link.span(),
+            "x25_switch_call",
+            called = %call_request.called_addr,
+            calling = %call_request.calling_addr
+        );
+
+        let mut dest_link = match self.connect(&call_request.called_addr) {
+            Ok(dest_link) => dest_link,
+            Err(err) => {
+                warn!(parent: &span, error = %err, "unable to route call");
+
+                let (cause_code, diagnostic_code) = if err.kind() == io::ErrorKind::NotFound {
+                    (13, 67) // Not obtainable - invalid called DTE address
+                } else {
+                    (9, 0) // Out of order
+                };
+
+                let clear_request = X25ClearRequest {
+                    modulo: call_request.modulo,
+                    channel: call_request.channel,
+                    cause_code,
+                    diagnostic_code,
+                    called_addr: X121Addr::null(),
+                    calling_addr: X121Addr::null(),
+                    facilities: Vec::new(),
+                    clear_user_data: bytes::Bytes::new(),
+                };
+
+                let mut buf = bytes::BytesMut::new();
+
+                X25Packet::from(clear_request)
+                    .encode(&mut buf)
+                    .map_err(io::Error::other)?;
+
+                return link.send(&buf);
+            }
+        };
+
+        info!(parent: &span, "call routed");
+
+        dest_link.send(&buf)?;
+
+        relay(link, dest_link, &span)
+    }
+
+    fn connect(&self, called_addr: &X121Addr) -> io::Result<XotLink> {
+        let Some(destination) = self.resolver.lookup(called_addr) else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no route"));
+        };
+
+        // A destination with a port, such as a local party, is used as is.
+        let addrs = match SocketAddr::from_str(&destination) {
+            Ok(addr) => vec![addr],
+            Err(_) => self.resolver.resolve(called_addr)?,
+        };
+
+        let tcp_stream = TcpStream::connect(&addrs[..])?;
+
+        Ok(XotLink::new(tcp_stream))
+    }
+}
+
+fn relay(calling_link: XotLink, called_link: XotLink, span: &Span) -> io::Result<()> {
+    let calling_send_link = calling_link.try_clone()?;
+    let called_send_link = called_link.try_clone()?;
+
+    let handle = thread::Builder::new()
+        .name("x25_switch_relay".to_string())
+        .spawn({
+            let span = span.clone();
+
+            move || forward(called_link, calling_send_link, &span)
+        })
+        .expect("failed to spawn thread");
+
+    forward(calling_link, called_send_link, span);
+
+    let _ = handle.join();
+
+    Ok(())
+}
+
+/// Forwards packets from one leg to the other, until the call is cleared or
+/// either link fails.
+fn forward(mut from: XotLink, mut to: XotLink, span: &Span) {
+    loop {
+        let buf = match from.recv() {
+            Ok(buf) => buf,
+            Err(err) => {
+                debug!(parent: span, error = %err, "link closed");
+                break;
+            }
+        };
+
+        let packet = X25Packet::decode(buf.clone());
+
+        match packet {
+            Ok(ref packet) => debug!(parent: span, ?packet, "packet relayed"),
+            Err(ref err) => debug!(parent: span, error = %err, "undecodable packet relayed"),
+        }
+
+        if let Err(err) = to.send(&buf) {
+            debug!(parent: span, error = %err, "link closed");
+            break;
+        }
+
+        // The call is complete once the clear is confirmed, closing both links
+        // ends the relay in the other direction too.
+        if matches!(packet, Ok(X25Packet::ClearConfirm(_))) {
+            info!(parent: span, "call cleared");
+            break;
+        }
+    }
+
+    let _ = from.shutdown();
+    let _ = to.shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    use crate::clock::SystemClock;
+    use crate::x25::{Svc, Vc, X25AddrFormat, X25Modulo, X25Params, X25Role};
+
+    fn params(addr: &str) -> X25Params {
+        X25Params {
+            addr: X121Addr::from_str(addr).unwrap(),
+            modulo: X25Modulo::Normal,
+            send_packet_size: 128,
+            send_window_size: 2,
+            recv_packet_size: 128,
+            recv_window_size: 2,
+            max_recv_sequence_len: None,
+            t21: Duration::from_secs(5),
+            t22: Duration::from_secs(5),
+            t23: Duration::from_secs(5),
+            addr_format: X25AddrFormat::NonToaNpi,
+            idle_probe: None,
+            role: X25Role::Dte,
+            channels: 1..=1024,
+        }
+    }
+
+    /// Starts a switch, with a host at 2222 that echoes data, returning the
+    /// switch address.
+    fn start() -> SocketAddr {
+        let host_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host_addr = host_listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (tcp_stream, _) = host_listener.accept().unwrap();
+
+            let incoming_call = Svc::listen_timeout(
+                XotLink::new(tcp_stream),
+                1,
+                &params("2222"),
+                Arc::new(SystemClock),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+            let svc = incoming_call.accept().unwrap();
+
+            while let Ok(Some((data, qualifier))) = svc.recv() {
+                svc.send(data, qualifier).unwrap();
+            }
+        });
+
+        let mut resolver = XotResolver::new();
+
+        resolver.add("2222", &host_addr.to_string()).unwrap();
+
+        let switch = Arc::new(X25Switch::new(resolver));
+
+        let switch_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let switch_addr = switch_listener.local_addr().unwrap();
+
+        thread::spawn(move || switch.serve(switch_listener));
+
+        switch_addr
+    }
+
+    #[test]
+    fn call_routed() {
+        let switch_addr = start();
+
+        let link = XotLink::new(TcpStream::connect(switch_addr).unwrap());
+        let addr = X121Addr::from_str("2222").unwrap();
+
+        let svc = Svc::call(link, 1, &addr, b"", &params("1111"), Arc::new(SystemClock)).unwrap();
+
+        svc.send(Bytes::from_static(b"hello"), false).unwrap();
+
+        assert_eq!(
+            svc.recv().unwrap(),
+            Some((Bytes::from_static(b"hello"), false))
+        );
+
+        assert!(svc.clear(0, 0).is_ok());
+    }
+
+    #[test]
+    fn call_not_routed() {
+        let switch_addr = start();
+
+        let link = XotLink::new(TcpStream::connect(switch_addr).unwrap());
+        let addr = X121Addr::from_str("3333").unwrap();
+
+        let err = Svc::call(link, 1, &addr, b"", &params("1111"), Arc::new(SystemClock))
+            .err()
+            .unwrap();
+
+        assert_eq!(err.to_string(), "C:13 D:67");
+    }
+}
@@ -0,0 +1,315 @@
+//! Test support.
+//!
+//! This module provides a scripted X.25 peer, allowing `Svc` and `Pad` to be
+//! tested against a peer that follows a script of expected and injected
+//! packets.
+//!
+//! It is only available with the `test-util` feature.
+
+use bytes::BytesMut;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::clock::ManualClock;
+use crate::x25::packet::{X25Packet, X25PacketType};
+use crate::xot::XotLink;
+
+/// The time the peer waits for a packet before failing.
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Creates a connected pair of XOT links, over a loopback TCP connection,
+/// returning the link for the code under test and the peer.
+pub fn peer_pair() -> io::Result<(XotLink, ScriptedPeer)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+
+    let stream = TcpStream::connect(listener.local_addr()?)?;
+
+    let (peer_stream, _) = listener.accept()?;
+
+    // A misbehaving test should fail, rather than wait forever.
+    peer_stream.set_read_timeout(Some(PEER_TIMEOUT))?;
+
+    let peer = ScriptedPeer {
+        link: XotLink::new(peer_stream),
+    };
+
+    Ok((XotLink::new(stream), peer))
+}
+
+/// X.25 peer, that can send and receive individual packets or follow an
+/// `X25Script`.
+pub struct ScriptedPeer {
+    link: XotLink,
+}
+
+impl ScriptedPeer {
+    /// Sends a packet.
+    pub fn send<P: Into<X25Packet>>(&mut self, packet: P) -> io::Result<()> {
+        send(&mut self.link, &packet.into())
+    }
+
+    /// Receives a packet.
+    pub fn recv(&mut self) -> io::Result<X25Packet> {
+        let buf = self.link.recv()?;
+
+        X25Packet::decode(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Runs the script, returning an error identifying the first step that
+    /// failed.
+    pub fn run(&mut self, script: &X25Script) -> Result<(), ScriptError> {
+        for (index, step) in script.steps.iter().enumerate() {
+            let error = |message: String| ScriptError {
+                step: index,
+                message,
+            };
+
+            match step {
+                Step::Expect(description, predicate) => {
+                    let packet = self
+                        .recv()
+                        .map_err(|err| error(format!("expected {description}, {err}")))?;
+
+                    if !predicate(&packet) {
+                        return Err(error(format!(
+                            "expected {description}, received {packet:?}"
+                        )));
+                    }
+                }
+                Step::ExpectPacket(expected) => {
+                    let expected_buf = encode(expected)
+                        .map_err(|err| error(format!("unable to encode {expected:?}, {err}")))?;
+
+                    let packet = self
+                        .recv()
+                        .map_err(|err| error(format!("expected {expected:?}, {err}")))?;
+
+                    let buf = encode(&packet)
+                        .map_err(|err| error(format!("unable to encode {packet:?}, {err}")))?;
+
+                    if buf != expected_buf {
+                        return Err(error(format!("expected {expected:?}, received {packet:?}")));
+                    }
+                }
+                Step::Send(packet) => {
+                    send(&mut self.link, packet)
+                        .map_err(|err| error(format!("unable to send {packet:?}, {err}")))?;
+                }
+                Step::Advance(clock, duration) => clock.advance(*duration),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the script on a new thread, the peer is returned when the script
+    /// completes successfully.
+    pub fn spawn(mut self, script: X25Script) -> JoinHandle<Result<ScriptedPeer, ScriptError>> {
+        thread::Builder::new()
+            .name("scripted_peer".to_string())
+            .spawn(move || {
+                self.run(&script)?;
+
+                Ok(self)
+            })
+            .expect("failed to spawn thread")
+    }
+}
+
+type Predicate = Box<dyn Fn(&X25Packet) -> bool + Send>;
+
+enum Step {
+    Expect(String, Predicate),
+    ExpectPacket(X25Packet),
+    Send(X25Packet),
+    Advance(Arc<ManualClock>, Duration),
+}
+
+/// Script of packets expected and sent by a `ScriptedPeer`.
+#[derive(Default)]
+pub struct X25Script {
+    steps: Vec<Step>,
+}
+
+impl X25Script {
+    pub fn new() -> Self {
+        X25Script::default()
+    }
+
+    /// Expects a packet matching the predicate, the description is used to
+    /// report a mismatch.
+    pub fn expect<F>(mut self, description: &str, predicate: F) -> Self
+    where
+        F: Fn(&X25Packet) -> bool + Send + 'static,
+    {
+        self.steps
+            .push(Step::Expect(description.to_string(), Box::new(predicate)));
+
+        self
+    }
+
+    /// Expects a packet of the type provided.
+    pub fn expect_type(self, packet_type: X25PacketType) -> Self {
+        let description = format!("{packet_type:?}");
+
+        self.expect(&description, move |p| p.packet_type() == packet_type)
+    }
+
+    /// Expects a packet that is identical, when encoded, to the packet provided.
+    ///
+    /// A packet that can't be encoded fails the script, rather than matching
+    /// any other packet that can't be encoded.
+    pub fn expect_packet<P: Into<X25Packet>>(mut self, packet: P) -> Self {
+        self.steps.push(Step::ExpectPacket(packet.into()));
+
+        self
+    }
+
+    /// Sends a packet.
+    pub fn send<P: Into<X25Packet>>(mut self, packet: P) -> Self {
+        self.steps.push(Step::Send(packet.into()));
+
+        self
+    }
+
+    /// Advances the clock, to expire timers.
+    pub fn advance(mut self, clock: &Arc<ManualClock>, duration: Duration) -> Self {
+        self.steps.push(Step::Advance(Arc::clone(clock), duration));
+
+        self
+    }
+}
+
+/// Error returned when a `ScriptedPeer` fails to follow an `X25Script`.
+#[derive(Debug)]
+pub struct ScriptError {
+    /// The index of the step that failed.
+    pub step: usize,
+
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "step {}: {}", self.step, self.message)
+    }
+}
+
+impl Error for ScriptError {}
+
+fn send(link: &mut XotLink, packet: &X25Packet) -> io::Result<()> {
+    let mut buf = BytesMut::new();
+
+    packet.encode(&mut buf).map_err(io::Error::other)?;
+
+    link.send(&buf)
+}
+
+fn encode(packet: &X25Packet) -> Result<BytesMut, String> {
+    let mut buf = BytesMut::new();
+
+    packet.encode(&mut buf)?;
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    use crate::x25::packet::{X25Interrupt, X25ReceiveReady};
+    use crate::x25::X25Modulo;
+
+    fn receive_ready(recv_seq: u8) -> X25ReceiveReady {
+        X25ReceiveReady {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            recv_seq,
+        }
+    }
+
+    fn run(script: X25Script, packets: &[X25Packet]) -> Result<(), ScriptError> {
+        let (mut link, peer) = peer_pair().unwrap();
+
+        let peer = peer.spawn(script);
+
+        for packet in packets {
+            send(&mut link, packet).unwrap();
+        }
+
+        peer.join().unwrap().map(|_| ())
+    }
+
+    #[test]
+    fn expect_match() {
+        let script = X25Script::new().expect_type(X25PacketType::ReceiveReady);
+
+        assert!(run(script, &[receive_ready(1).into()]).is_ok());
+    }
+
+    #[test]
+    fn expect_mismatch() {
+        let script = X25Script::new().expect_type(X25PacketType::ClearRequest);
+
+        let err = run(script, &[receive_ready(1).into()]).unwrap_err();
+
+        assert_eq!(err.step, 0);
+        assert!(err.message.starts_with("expected ClearRequest, received"));
+    }
+
+    #[test]
+    fn expect_packet_match() {
+        let script = X25Script::new().expect_packet(receive_ready(1));
+
+        assert!(run(script, &[receive_ready(1).into()]).is_ok());
+    }
+
+    #[test]
+    fn expect_packet_mismatch() {
+        let script = X25Script::new().expect_packet(receive_ready(2));
+
+        let err = run(script, &[receive_ready(1).into()]).unwrap_err();
+
+        assert_eq!(err.step, 0);
+        assert!(err.message.contains("received"));
+    }
+
+    #[test]
+    fn expect_packet_invalid() {
+        // Interrupt user data is required, so this can't be encoded.
+        let interrupt = X25Interrupt {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            user_data: Bytes::new(),
+        };
+
+        let script = X25Script::new().expect_packet(interrupt);
+
+        let err = run(script, &[receive_ready(1).into()]).unwrap_err();
+
+        assert_eq!(err.step, 0);
+        assert!(err.message.starts_with("unable to encode"));
+    }
+
+    #[test]
+    fn script_error_step() {
+        let script = X25Script::new()
+            .expect_type(X25PacketType::ReceiveReady)
+            .expect_packet(receive_ready(2));
+
+        let packets = [receive_ready(1).into(), receive_ready(3).into()];
+
+        let err = run(script, &packets).unwrap_err();
+
+        assert_eq!(err.step, 1);
+        assert!(err
+            .to_string()
+            .starts_with("step 1: expected ReceiveReady("));
+    }
+}
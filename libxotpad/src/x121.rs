@@ -1,14 +1,27 @@
 //! X.121 addressing.
 //!
 //! This module provides functionality for handling X.121 addresses.
+//!
+//! An international data number consists of a four digit _data network
+//! identification code_ (DNIC), made up of a three digit _data country code_
+//! (DCC) and a network digit, followed by the _network terminal number_ (NTN).
+//!
+//! An address may also have a _type of address_ (TOA) and _numbering plan
+//! identification_ (NPI), allowing E.164 and national numbers to be carried
+//! in the TOA/NPI address format.
+//!
+//! Addresses can be matched using an `X121Pattern`, which may also identify a
+//! subaddress following the main address.
 
 use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 
 /// X.121 address.
 #[derive(Clone, PartialEq, Debug)]
 pub struct X121Addr {
     addr: String,
+    addr_type: Option<(X121AddrType, X121NumberingPlan)>,
 }
 
 impl X121Addr {
@@ -16,6 +29,7 @@ impl X121Addr {
     pub fn null() -> Self {
         X121Addr {
             addr: String::new(),
+            addr_type: None,
         }
     }
 
@@ -55,6 +69,51 @@ impl X121Addr {
     pub fn starts_with(&self, prefix: &X121Addr) -> bool {
         self.addr.starts_with(&prefix.addr)
     }
+
+    /// Returns this address with the TOA/NPI provided.
+    pub fn with_type(mut self, toa: X121AddrType, npi: X121NumberingPlan) -> Self {
+        self.addr_type = Some((toa, npi));
+        self
+    }
+
+    /// Returns this address without a TOA/NPI.
+    pub fn without_type(mut self) -> Self {
+        self.addr_type = None;
+        self
+    }
+
+    /// Returns the TOA/NPI, or `None` if the address has no TOA/NPI.
+    pub fn addr_type(&self) -> Option<(X121AddrType, X121NumberingPlan)> {
+        self.addr_type
+    }
+
+    /// Returns the DNIC, assuming the address is an international data number,
+    /// or `None` if the address is too short or the DCC is not possible.
+    pub fn dnic(&self) -> Option<X121Dnic> {
+        // Only an X.121 number has a DNIC.
+        if let Some((_, npi)) = self.addr_type {
+            if npi != X121NumberingPlan::X121 && npi != X121NumberingPlan::Unknown {
+                return None;
+            }
+        }
+
+        let dnic = self.addr.get(..4)?;
+
+        X121Dnic::from_str(dnic).ok()
+    }
+
+    /// Returns the NTN, the digits following the DNIC, or `None` if the address
+    /// has no DNIC.
+    pub fn ntn(&self) -> Option<&str> {
+        self.dnic()?;
+
+        Some(&self.addr[4..])
+    }
+
+    /// Returns the known network for the DNIC, if any.
+    pub fn network(&self) -> Option<X121Network> {
+        self.dnic()?.network()
+    }
 }
 
 impl fmt::Display for X121Addr {
@@ -76,14 +135,414 @@ impl FromStr for X121Addr {
             return Err("all characters must be digits between 0 and 9".into());
         }
 
-        Ok(X121Addr { addr: s.into() })
+        Ok(X121Addr {
+            addr: s.into(),
+            addr_type: None,
+        })
+    }
+}
+
+/// Pattern matching X.121 addresses.
+///
+/// A pattern is made up of:
+///
+///   - a digit, matching that digit
+///   - `X`, matching any digit
+///   - `[...]`, matching a class of digits, such as `[0-4]` or `[13579]`
+///   - `(...)`, capturing the digits matched, for use in a resolver template
+///
+/// optionally followed by either:
+///
+///   - `*`, matching any number of further digits
+///   - `+N`, matching up to `N` subaddress digits, or `+` for any number
+///
+/// A pattern must match the entire address, for example `73741100+2` matches
+/// `73741100` followed by up to two subaddress digits.
+#[derive(Clone, Debug)]
+pub struct X121Pattern {
+    pattern: String,
+
+    /// The digits matched at each position, as a bit mask.
+    digits: Vec<u16>,
+
+    captures: Vec<Range<usize>>,
+    rest: X121PatternRest,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum X121PatternRest {
+    None,
+    Any,
+    Subaddr(Option<usize>),
+}
+
+const ANY_DIGIT: u16 = 0x03ff;
+
+impl X121Pattern {
+    /// Creates a new `X121Pattern` matching the address provided, followed by up
+    /// to `max_subaddr_len` subaddress digits, or any number if `None`.
+    pub fn with_subaddr(addr: &X121Addr, max_subaddr_len: Option<usize>) -> Self {
+        let pattern = match max_subaddr_len {
+            Some(len) => format!("{addr}+{len}"),
+            None => format!("{addr}+"),
+        };
+
+        X121Pattern {
+            pattern,
+            digits: addr.digits().map(|d| 1 << d).collect(),
+            captures: Vec::new(),
+            rest: X121PatternRest::Subaddr(max_subaddr_len),
+        }
+    }
+
+    /// Returns `true` if the address matches this pattern.
+    pub fn is_match(&self, addr: &X121Addr) -> bool {
+        self.matches(addr).is_some()
+    }
+
+    /// Matches the address against this pattern, returning the captures and
+    /// subaddress if the address matches.
+    pub fn matches<'a>(&self, addr: &'a X121Addr) -> Option<X121Match<'a>> {
+        let addr = addr.addr.as_str();
+
+        if addr.len() < self.digits.len() {
+            return None;
+        }
+
+        let (main, rest) = addr.split_at(self.digits.len());
+
+        let is_main_match = main
+            .bytes()
+            .zip(&self.digits)
+            .all(|(d, mask)| mask & (1 << (d - b'0')) != 0);
+
+        if !is_main_match {
+            return None;
+        }
+
+        let subaddr = match self.rest {
+            X121PatternRest::None if rest.is_empty() => "",
+            X121PatternRest::None => return None,
+            X121PatternRest::Any => "",
+            X121PatternRest::Subaddr(Some(max_len)) if rest.len() > max_len => return None,
+            X121PatternRest::Subaddr(_) => rest,
+        };
+
+        let captures = self.captures.iter().map(|r| &addr[r.clone()]).collect();
+
+        Some(X121Match { captures, subaddr })
+    }
+}
+
+impl fmt::Display for X121Pattern {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pattern.fmt(fmt)
+    }
+}
+
+impl FromStr for X121Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut digits = Vec::new();
+        let mut captures = Vec::new();
+        let mut capture_start = None;
+        let mut rest = X121PatternRest::None;
+
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '0'..='9' => digits.push(1 << c.to_digit(10).unwrap()),
+                'X' | 'x' => digits.push(ANY_DIGIT),
+                '[' => {
+                    let mut class = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => class.push(c),
+                            None => return Err("unclosed [".into()),
+                        }
+                    }
+
+                    digits.push(parse_digit_class(&class)?);
+                }
+                '(' if capture_start.is_none() => capture_start = Some(digits.len()),
+                ')' => {
+                    let Some(start) = capture_start.take() else {
+                        return Err("unexpected )".into());
+                    };
+
+                    captures.push(start..digits.len());
+                }
+                '*' => {
+                    rest = X121PatternRest::Any;
+                    break;
+                }
+                '+' => {
+                    let len = chars.by_ref().collect::<String>();
+
+                    let max_len = if len.is_empty() {
+                        None
+                    } else {
+                        Some(
+                            usize::from_str(&len)
+                                .map_err(|_| format!("invalid subaddress length: {len}"))?,
+                        )
+                    };
+
+                    rest = X121PatternRest::Subaddr(max_len);
+                }
+                _ => return Err(format!("invalid character: {c}")),
+            }
+        }
+
+        if chars.next().is_some() {
+            return Err("* must be at the end".into());
+        }
+
+        if capture_start.is_some() {
+            return Err("unclosed (".into());
+        }
+
+        if digits.len() > 15 {
+            return Err("too long".into());
+        }
+
+        Ok(X121Pattern {
+            pattern: s.into(),
+            digits,
+            captures,
+            rest,
+        })
+    }
+}
+
+fn parse_digit_class(class: &str) -> Result<u16, String> {
+    let mut mask = 0;
+    let mut chars = class.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let Some(start) = c.to_digit(10) else {
+            return Err(format!("invalid digit class: [{class}]"));
+        };
+
+        let end = if chars.next_if_eq(&'-').is_some() {
+            chars
+                .next()
+                .and_then(|c| c.to_digit(10))
+                .filter(|&end| end >= start)
+                .ok_or_else(|| format!("invalid digit class: [{class}]"))?
+        } else {
+            start
+        };
+
+        for digit in start..=end {
+            mask |= 1 << digit;
+        }
+    }
+
+    if mask == 0 {
+        return Err("empty digit class".into());
+    }
+
+    Ok(mask)
+}
+
+/// Result of matching an address against an `X121Pattern`.
+#[derive(Debug)]
+pub struct X121Match<'a> {
+    captures: Vec<&'a str>,
+    subaddr: &'a str,
+}
+
+impl<'a> X121Match<'a> {
+    /// Returns the digits captured by each group.
+    pub fn captures(&self) -> &[&'a str] {
+        &self.captures
+    }
+
+    /// Returns the subaddress digits, which are empty if the address has no
+    /// subaddress.
+    pub fn subaddr(&self) -> &'a str {
+        self.subaddr
+    }
+}
+
+/// Type of address, in the TOA/NPI address format.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum X121AddrType {
+    /// Network dependent number, or unknown.
+    Unknown,
+    International,
+    National,
+    NetworkSpecific,
+
+    /// Complementary address, without a main address.
+    Complementary,
+
+    Alternative,
+    Reserved(u8),
+}
+
+impl From<u8> for X121AddrType {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0 => X121AddrType::Unknown,
+            1 => X121AddrType::International,
+            2 => X121AddrType::National,
+            3 => X121AddrType::NetworkSpecific,
+            4 => X121AddrType::Complementary,
+            5 => X121AddrType::Alternative,
+            value => X121AddrType::Reserved(value),
+        }
+    }
+}
+
+impl From<X121AddrType> for u8 {
+    fn from(toa: X121AddrType) -> u8 {
+        match toa {
+            X121AddrType::Unknown => 0,
+            X121AddrType::International => 1,
+            X121AddrType::National => 2,
+            X121AddrType::NetworkSpecific => 3,
+            X121AddrType::Complementary => 4,
+            X121AddrType::Alternative => 5,
+            X121AddrType::Reserved(value) => value & 0x0f,
+        }
+    }
+}
+
+/// Numbering plan identification, in the TOA/NPI address format.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum X121NumberingPlan {
+    Unknown,
+    E164,
+    X121,
+    Reserved(u8),
+}
+
+impl From<u8> for X121NumberingPlan {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0 => X121NumberingPlan::Unknown,
+            1 => X121NumberingPlan::E164,
+            3 => X121NumberingPlan::X121,
+            value => X121NumberingPlan::Reserved(value),
+        }
+    }
+}
+
+impl From<X121NumberingPlan> for u8 {
+    fn from(npi: X121NumberingPlan) -> u8 {
+        match npi {
+            X121NumberingPlan::Unknown => 0,
+            X121NumberingPlan::E164 => 1,
+            X121NumberingPlan::X121 => 3,
+            X121NumberingPlan::Reserved(value) => value & 0x0f,
+        }
+    }
+}
+
+/// Data network identification code.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct X121Dnic(u16);
+
+impl X121Dnic {
+    /// Returns the DCC.
+    pub fn dcc(&self) -> u16 {
+        self.0 / 10
+    }
+
+    /// Returns the network digit, identifying a network within the country.
+    pub fn network_digit(&self) -> u8 {
+        (self.0 % 10) as u8
+    }
+
+    /// Returns the known network for this DNIC, if any.
+    pub fn network(&self) -> Option<X121Network> {
+        let &(_, name, country) = NETWORKS.iter().find(|&&(dnic, _, _)| dnic == self.0)?;
+
+        Some(X121Network { name, country })
     }
 }
 
+impl fmt::Display for X121Dnic {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{:04}", self.0)
+    }
+}
+
+impl FromStr for X121Dnic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        if s.len() != 4 || !s.chars().all(|c| c.is_ascii_digit()) {
+            return Err("must be 4 digits".into());
+        }
+
+        let dnic = u16::from_str(s).unwrap();
+
+        // Only zones 2 to 7 are allocated to countries, the other leading digits
+        // are escape codes or reserved.
+        let zone = dnic / 1000;
+
+        if !(2..=7).contains(&zone) {
+            return Err(format!("impossible DCC: {}", dnic / 10));
+        }
+
+        Ok(X121Dnic(dnic))
+    }
+}
+
+/// Public data network.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct X121Network {
+    pub name: &'static str,
+    pub country: &'static str,
+}
+
+impl fmt::Display for X121Network {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}, {}", self.name, self.country)
+    }
+}
+
+/// Known public data networks, the DNIC, network name and country.
+static NETWORKS: &[(u16, &str, &str)] = &[
+    (2041, "Datanet 1", "Netherlands"),
+    (2062, "DCS", "Belgium"),
+    (2080, "Transpac", "France"),
+    (2145, "Iberpac", "Spain"),
+    (2222, "Itapac", "Italy"),
+    (2284, "Telepac", "Switzerland"),
+    (2322, "Datex-P", "Austria"),
+    (2342, "PSS", "UK"),
+    (2382, "Datapak", "Denmark"),
+    (2405, "Datapak", "Sweden"),
+    (2422, "Datapak", "Norway"),
+    (2442, "Datapak", "Finland"),
+    (2624, "Datex-P", "Germany"),
+    (2680, "Telepac", "Portugal"),
+    (2724, "Eirpac", "Ireland"),
+    (3020, "Datapac", "Canada"),
+    (3106, "Tymnet", "USA"),
+    (3110, "Telenet", "USA"),
+    (4401, "DDX-P", "Japan"),
+    (4408, "Venus-P", "Japan"),
+    (5052, "Austpac", "Australia"),
+    (5301, "Pacnet", "New Zealand"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn addr(s: &str) -> X121Addr {
+        X121Addr::from_str(s).unwrap()
+    }
+
     #[test]
     fn from_str_with_null_input() {
         let addr = X121Addr::from_str("");
@@ -170,4 +629,111 @@ mod tests {
 
         assert_eq!(digits, [7, 3, 7, 4, 1, 1, 0, 0]);
     }
+
+    #[test]
+    fn dnic_and_ntn() {
+        let addr = X121Addr::from_str("311090100123").unwrap();
+
+        let dnic = addr.dnic().unwrap();
+
+        assert_eq!(dnic.to_string(), "3110");
+        assert_eq!(dnic.dcc(), 311);
+        assert_eq!(dnic.network_digit(), 0);
+        assert_eq!(addr.ntn(), Some("90100123"));
+        assert_eq!(addr.network().unwrap().to_string(), "Telenet, USA");
+    }
+
+    #[test]
+    fn dnic_unknown_network() {
+        let addr = X121Addr::from_str("73741100").unwrap();
+
+        assert_eq!(addr.dnic().unwrap().dcc(), 737);
+        assert!(addr.network().is_none());
+    }
+
+    #[test]
+    fn dnic_e164() {
+        let addr = X121Addr::from_str("311090100123")
+            .unwrap()
+            .with_type(X121AddrType::International, X121NumberingPlan::E164);
+
+        assert!(addr.dnic().is_none());
+        assert_eq!(addr.to_string(), "311090100123");
+    }
+
+    #[test]
+    fn addr_type_round_trip() {
+        for value in 0..16 {
+            assert_eq!(u8::from(X121AddrType::from(value)), value);
+            assert_eq!(u8::from(X121NumberingPlan::from(value)), value);
+        }
+    }
+
+    #[test]
+    fn dnic_impossible_dcc() {
+        assert!(X121Addr::from_str("01234567").unwrap().dnic().is_none());
+        assert!(X121Addr::from_str("91234567").unwrap().dnic().is_none());
+        assert!(X121Addr::from_str("123").unwrap().ntn().is_none());
+
+        assert!(X121Dnic::from_str("1234").is_err());
+        assert!(X121Dnic::from_str("234").is_err());
+    }
+
+    #[test]
+    fn pattern_digit_class() {
+        let pattern = X121Pattern::from_str("737[0-3]X[13]").unwrap();
+
+        assert!(pattern.is_match(&addr("737291")));
+        assert!(pattern.is_match(&addr("737003")));
+        assert!(!pattern.is_match(&addr("737491")));
+        assert!(!pattern.is_match(&addr("737292")));
+        assert!(!pattern.is_match(&addr("7372911")));
+        assert!(!pattern.is_match(&addr("73729")));
+    }
+
+    #[test]
+    fn pattern_prefix() {
+        let pattern = X121Pattern::from_str("(XXX)(XXX)*").unwrap();
+
+        let addr = addr("73741100");
+        let m = pattern.matches(&addr).unwrap();
+
+        assert_eq!(m.captures(), ["737", "411"]);
+        assert_eq!(m.subaddr(), "");
+
+        assert!(X121Pattern::from_str("*")
+            .unwrap()
+            .is_match(&X121Addr::null()));
+    }
+
+    #[test]
+    fn pattern_subaddr() {
+        let pattern = X121Pattern::with_subaddr(&addr("73741100"), Some(2));
+
+        assert_eq!(pattern.to_string(), "73741100+2");
+
+        let addr1 = addr("7374110012");
+
+        assert_eq!(pattern.matches(&addr1).unwrap().subaddr(), "12");
+        assert_eq!(pattern.matches(&addr("73741100")).unwrap().subaddr(), "");
+        assert_eq!(pattern.matches(&addr("737411001")).unwrap().subaddr(), "1");
+        assert!(!pattern.is_match(&addr("737411001234")));
+
+        let pattern = X121Pattern::from_str("73741100+").unwrap();
+
+        assert!(pattern.is_match(&addr("737411001234")));
+    }
+
+    #[test]
+    fn pattern_from_str_invalid() {
+        assert!(X121Pattern::from_str("12a").is_err());
+        assert!(X121Pattern::from_str("1[]").is_err());
+        assert!(X121Pattern::from_str("1[23").is_err());
+        assert!(X121Pattern::from_str("1[5-3]").is_err());
+        assert!(X121Pattern::from_str("(12").is_err());
+        assert!(X121Pattern::from_str("12)").is_err());
+        assert!(X121Pattern::from_str("12*3").is_err());
+        assert!(X121Pattern::from_str("12+x").is_err());
+        assert!(X121Pattern::from_str("1234567890123456").is_err());
+    }
 }
@@ -8,9 +8,11 @@ pub mod facility;
 pub mod packet;
 mod params;
 mod seq;
+mod timer;
 mod vc;
 
 pub use self::packet::{MAX_PACKET_LEN, MIN_PACKET_LEN};
-pub use self::params::X25Params;
+pub use self::params::{X25AddrFormat, X25Params, X25Role};
 pub use self::seq::X25Modulo;
-pub use self::vc::{Svc, SvcIncomingCall, Vc};
+pub use self::timer::X25Timer;
+pub use self::vc::{Svc, SvcCallCollision, SvcIncomingCall, SvcStats, Vc, VcEvent};
@@ -4,7 +4,7 @@
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
-use crate::x121::X121Addr;
+use crate::x121::{X121Addr, X121AddrType, X121NumberingPlan};
 use crate::x25::facility::{decode_facilities, encode_facilities, X25Facility};
 use crate::x25::seq::X25Modulo;
 
@@ -28,8 +28,8 @@ pub enum X25Packet {
     ClearRequest(X25ClearRequest),
     ClearConfirm(X25ClearConfirm),
     Data(X25Data),
-    // TODO: Interrupt
-    // TODO: InterruptConfirm
+    Interrupt(X25Interrupt),
+    InterruptConfirm(X25InterruptConfirm),
     ReceiveReady(X25ReceiveReady),
     ReceiveNotReady(X25ReceiveNotReady),
     // TODO: Reject
@@ -48,8 +48,8 @@ pub enum X25PacketType {
     ClearRequest,
     ClearConfirm,
     Data,
-    // TODO: Interrupt
-    // TODO: InterruptConfirm
+    Interrupt,
+    InterruptConfirm,
     ReceiveReady,
     ReceiveNotReady,
     // TODO: Reject
@@ -69,6 +69,8 @@ impl X25Packet {
             X25Packet::ClearRequest(_) => X25PacketType::ClearRequest,
             X25Packet::ClearConfirm(_) => X25PacketType::ClearConfirm,
             X25Packet::Data(_) => X25PacketType::Data,
+            X25Packet::Interrupt(_) => X25PacketType::Interrupt,
+            X25Packet::InterruptConfirm(_) => X25PacketType::InterruptConfirm,
             X25Packet::ReceiveReady(_) => X25PacketType::ReceiveReady,
             X25Packet::ReceiveNotReady(_) => X25PacketType::ReceiveNotReady,
             X25Packet::ResetRequest(_) => X25PacketType::ResetRequest,
@@ -84,6 +86,8 @@ impl X25Packet {
             X25Packet::ClearRequest(clear_request) => clear_request.modulo,
             X25Packet::ClearConfirm(clear_confirm) => clear_confirm.modulo,
             X25Packet::Data(data) => data.modulo,
+            X25Packet::Interrupt(interrupt) => interrupt.modulo,
+            X25Packet::InterruptConfirm(interrupt_confirm) => interrupt_confirm.modulo,
             X25Packet::ReceiveReady(receive_ready) => receive_ready.modulo,
             X25Packet::ReceiveNotReady(receive_not_ready) => receive_not_ready.modulo,
             X25Packet::ResetRequest(reset_request) => reset_request.modulo,
@@ -99,6 +103,8 @@ impl X25Packet {
             X25Packet::ClearRequest(clear_request) => Some(clear_request.channel),
             X25Packet::ClearConfirm(clear_confirm) => Some(clear_confirm.channel),
             X25Packet::Data(data) => Some(data.channel),
+            X25Packet::Interrupt(interrupt) => Some(interrupt.channel),
+            X25Packet::InterruptConfirm(interrupt_confirm) => Some(interrupt_confirm.channel),
             X25Packet::ReceiveReady(receive_ready) => Some(receive_ready.channel),
             X25Packet::ReceiveNotReady(receive_not_ready) => Some(receive_not_ready.channel),
             X25Packet::ResetRequest(reset_request) => Some(reset_request.channel),
@@ -114,6 +120,8 @@ impl X25Packet {
             X25Packet::ClearRequest(clear_request) => clear_request.encode(buf),
             X25Packet::ClearConfirm(clear_confirm) => clear_confirm.encode(buf),
             X25Packet::Data(data) => data.encode(buf),
+            X25Packet::Interrupt(interrupt) => interrupt.encode(buf),
+            X25Packet::InterruptConfirm(interrupt_confirm) => interrupt_confirm.encode(buf),
             X25Packet::ReceiveReady(receive_ready) => receive_ready.encode(buf),
             X25Packet::ReceiveNotReady(receive_not_ready) => receive_not_ready.encode(buf),
             X25Packet::ResetRequest(reset_request) => reset_request.encode(buf),
@@ -149,6 +157,14 @@ impl X25Packet {
             let clear_confirm = X25ClearConfirm::decode(buf, modulo, gfi, channel, type_)?;
 
             Ok(X25Packet::ClearConfirm(clear_confirm))
+        } else if type_ == 0x23 {
+            let interrupt = X25Interrupt::decode(buf, modulo, gfi, channel, type_)?;
+
+            Ok(X25Packet::Interrupt(interrupt))
+        } else if type_ == 0x27 {
+            let interrupt_confirm = X25InterruptConfirm::decode(buf, modulo, gfi, channel, type_)?;
+
+            Ok(X25Packet::InterruptConfirm(interrupt_confirm))
         } else if type_ & 0x01 == 0x00 {
             let data = X25Data::decode(buf, modulo, gfi, channel, type_)?;
 
@@ -191,7 +207,9 @@ impl X25CallRequest {
     pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
         let mut len = 0;
 
-        len += encode_packet_header(self.modulo, 0, self.channel, 0x0b, buf)?;
+        let gfi_overlay = addr_format_gfi(&self.called_addr, &self.calling_addr);
+
+        len += encode_packet_header(self.modulo, gfi_overlay, self.channel, 0x0b, buf)?;
         len += encode_addr_block(&self.called_addr, &self.calling_addr, buf);
         len += encode_facilities_block(&self.facilities, buf)?;
 
@@ -210,7 +228,7 @@ impl X25CallRequest {
     fn decode(
         mut buf: Bytes,
         modulo: X25Modulo,
-        _gfi: u8,
+        gfi: u8,
         channel: u16,
         type_: u8,
     ) -> Result<Self, String> {
@@ -226,7 +244,7 @@ impl X25CallRequest {
 
         buf.advance(3);
 
-        let (called_addr, calling_addr) = decode_addr_block(&mut buf)?;
+        let (called_addr, calling_addr) = decode_addr_block(&mut buf, gfi)?;
         let facilities = decode_facilities_block(&mut buf)?;
 
         let call_user_data = if buf.has_remaining() {
@@ -268,7 +286,9 @@ impl X25CallAccept {
     pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
         let mut len = 0;
 
-        len += encode_packet_header(self.modulo, 0, self.channel, 0x0f, buf)?;
+        let gfi_overlay = addr_format_gfi(&self.called_addr, &self.calling_addr);
+
+        len += encode_packet_header(self.modulo, gfi_overlay, self.channel, 0x0f, buf)?;
 
         let has_addr = !self.called_addr.is_null() || !self.calling_addr.is_null();
         let has_facilities = !self.facilities.is_empty();
@@ -296,7 +316,7 @@ impl X25CallAccept {
     fn decode(
         mut buf: Bytes,
         modulo: X25Modulo,
-        _gfi: u8,
+        gfi: u8,
         channel: u16,
         type_: u8,
     ) -> Result<Self, String> {
@@ -313,7 +333,7 @@ impl X25CallAccept {
         buf.advance(3);
 
         let (called_addr, calling_addr) = if buf.has_remaining() {
-            decode_addr_block(&mut buf)?
+            decode_addr_block(&mut buf, gfi)?
         } else {
             (X121Addr::null(), X121Addr::null())
         };
@@ -365,7 +385,9 @@ impl X25ClearRequest {
     pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
         let mut len = 0;
 
-        len += encode_packet_header(self.modulo, 0, self.channel, 0x13, buf)?;
+        let gfi_overlay = addr_format_gfi(&self.called_addr, &self.calling_addr);
+
+        len += encode_packet_header(self.modulo, gfi_overlay, self.channel, 0x13, buf)?;
 
         buf.put_u8(self.cause_code);
         len += 1;
@@ -427,7 +449,7 @@ impl X25ClearRequest {
         let diagnostic_code = if buf.has_remaining() { buf.get_u8() } else { 0 };
 
         let (called_addr, calling_addr) = if buf.has_remaining() {
-            decode_addr_block(&mut buf)?
+            decode_addr_block(&mut buf, gfi)?
         } else {
             (X121Addr::null(), X121Addr::null())
         };
@@ -478,7 +500,9 @@ impl X25ClearConfirm {
     pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
         let mut len = 0;
 
-        len += encode_packet_header(self.modulo, 0, self.channel, 0x17, buf)?;
+        let gfi_overlay = addr_format_gfi(&self.called_addr, &self.calling_addr);
+
+        len += encode_packet_header(self.modulo, gfi_overlay, self.channel, 0x17, buf)?;
 
         let has_addr = !self.called_addr.is_null() || !self.calling_addr.is_null();
         let has_facilities = !self.facilities.is_empty();
@@ -522,7 +546,7 @@ impl X25ClearConfirm {
         buf.advance(3);
 
         let (called_addr, calling_addr) = if buf.has_remaining() {
-            decode_addr_block(&mut buf)?
+            decode_addr_block(&mut buf, gfi)?
         } else {
             (X121Addr::null(), X121Addr::null())
         };
@@ -690,6 +714,122 @@ impl From<X25Data> for X25Packet {
     }
 }
 
+/// Maximum length of interrupt user data.
+pub const MAX_INTERRUPT_USER_DATA_LEN: usize = 32;
+
+/// X.25 _interrupt_ packet.
+#[derive(Clone, Debug)]
+pub struct X25Interrupt {
+    pub modulo: X25Modulo,
+    pub channel: u16,
+    pub user_data: Bytes,
+}
+
+impl X25Interrupt {
+    /// Encodes this `X25Interrupt` into the buffer provided.
+    pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
+        let user_data_len = self.user_data.len();
+
+        if user_data_len == 0 {
+            return Err("interrupt user data required".into());
+        }
+
+        if user_data_len > MAX_INTERRUPT_USER_DATA_LEN {
+            return Err(format!("interrupt user data too long: {user_data_len}"));
+        }
+
+        let mut len = 0;
+
+        len += encode_packet_header(self.modulo, 0, self.channel, 0x23, buf)?;
+
+        buf.put_slice(&self.user_data);
+        len += user_data_len;
+
+        Ok(len)
+    }
+
+    fn decode(
+        mut buf: Bytes,
+        modulo: X25Modulo,
+        gfi: u8,
+        channel: u16,
+        type_: u8,
+    ) -> Result<Self, String> {
+        assert_eq!(type_, 0x23);
+
+        if buf.len() < 4 {
+            return Err(format!("packet too short: {}", buf.len()));
+        }
+
+        if buf.len() > 3 + MAX_INTERRUPT_USER_DATA_LEN {
+            return Err(format!("packet too long: {}", buf.len()));
+        }
+
+        if (gfi & 0x0c) != 0x00 {
+            return Err(format!("invalid GFI: {gfi}"));
+        }
+
+        buf.advance(3);
+
+        Ok(X25Interrupt {
+            modulo,
+            channel,
+            user_data: buf,
+        })
+    }
+}
+
+impl From<X25Interrupt> for X25Packet {
+    fn from(interrupt: X25Interrupt) -> X25Packet {
+        X25Packet::Interrupt(interrupt)
+    }
+}
+
+/// X.25 _interrupt confirmation_ packet.
+#[derive(Debug)]
+pub struct X25InterruptConfirm {
+    pub modulo: X25Modulo,
+    pub channel: u16,
+}
+
+impl X25InterruptConfirm {
+    /// Encodes this `X25InterruptConfirm` into the buffer provided.
+    pub fn encode(&self, buf: &mut BytesMut) -> Result<usize, String> {
+        encode_packet_header(self.modulo, 0, self.channel, 0x27, buf)
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn decode(
+        buf: Bytes,
+        modulo: X25Modulo,
+        gfi: u8,
+        channel: u16,
+        type_: u8,
+    ) -> Result<Self, String> {
+        assert_eq!(type_, 0x27);
+
+        if buf.len() < 3 {
+            return Err(format!("packet too short: {}", buf.len()));
+        }
+
+        if buf.len() > 3 {
+            return Err(format!("packet too long: {}", buf.len()));
+        }
+
+        if (gfi & 0x0c) != 0x00 {
+            return Err(format!("invalid GFI: {gfi}"));
+        }
+
+        Ok(X25InterruptConfirm { modulo, channel })
+    }
+}
+
+impl From<X25InterruptConfirm> for X25Packet {
+    fn from(interrupt_confirm: X25InterruptConfirm) -> X25Packet {
+        X25Packet::InterruptConfirm(interrupt_confirm)
+    }
+}
+
 /// X.25 _receive ready_ packet.
 #[derive(Debug)]
 pub struct X25ReceiveReady {
@@ -883,7 +1023,7 @@ impl From<X25ReceiveNotReady> for X25Packet {
 }
 
 /// X.25 _reset request_ packet.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct X25ResetRequest {
     pub modulo: X25Modulo,
     pub channel: u16,
@@ -1036,7 +1176,27 @@ fn decode_packet_header(buf: &Bytes) -> Result<(X25Modulo, u8, u16, u8), String>
     Ok((modulo, gfi, channel, type_))
 }
 
+/// The A-bit, in the GFI of call setup and clearing packets, indicating the
+/// TOA/NPI address format.
+const GFI_A_BIT: u8 = 0x08;
+
+/// Returns the GFI overlay for the address format, the TOA/NPI address format
+/// is used if either address has a TOA/NPI.
+fn addr_format_gfi(called: &X121Addr, calling: &X121Addr) -> u8 {
+    let has_type = |a: &X121Addr| !a.is_null() && a.addr_type().is_some();
+
+    if has_type(called) || has_type(calling) {
+        GFI_A_BIT
+    } else {
+        0
+    }
+}
+
 fn encode_addr_block(called: &X121Addr, calling: &X121Addr, buf: &mut BytesMut) -> usize {
+    if addr_format_gfi(called, calling) == GFI_A_BIT {
+        return encode_toa_npi_addr_block(called, calling, buf);
+    }
+
     buf.put_u8(u8::try_from(calling.len()).unwrap() << 4 | u8::try_from(called.len()).unwrap());
 
     let mut len = 1;
@@ -1044,7 +1204,50 @@ fn encode_addr_block(called: &X121Addr, calling: &X121Addr, buf: &mut BytesMut)
     // Combine called and calling address digits.
     let digits: Vec<u8> = called.digits().chain(calling.digits()).collect();
 
-    for pair in digits.chunks(2) {
+    len += encode_semi_octets(&digits, buf);
+
+    len
+}
+
+/// Encodes the address block in the TOA/NPI address format, where the length
+/// of each address is a full octet and includes the TOA and NPI semi-octets
+/// that prefix the address digits.
+fn encode_toa_npi_addr_block(called: &X121Addr, calling: &X121Addr, buf: &mut BytesMut) -> usize {
+    let called = toa_npi_semi_octets(called);
+    let calling = toa_npi_semi_octets(calling);
+
+    buf.put_u8(u8::try_from(called.len()).unwrap());
+    buf.put_u8(u8::try_from(calling.len()).unwrap());
+
+    let mut len = 2;
+
+    let semi_octets: Vec<u8> = called.into_iter().chain(calling).collect();
+
+    len += encode_semi_octets(&semi_octets, buf);
+
+    len
+}
+
+fn toa_npi_semi_octets(addr: &X121Addr) -> Vec<u8> {
+    if addr.is_null() {
+        return Vec::new();
+    }
+
+    // An address without a TOA/NPI is assumed to be an X.121 address.
+    let (toa, npi) = addr
+        .addr_type()
+        .unwrap_or((X121AddrType::Unknown, X121NumberingPlan::X121));
+
+    [u8::from(toa), u8::from(npi)]
+        .into_iter()
+        .chain(addr.digits())
+        .collect()
+}
+
+fn encode_semi_octets(semi_octets: &[u8], buf: &mut BytesMut) -> usize {
+    let mut len = 0;
+
+    for pair in semi_octets.chunks(2) {
         let high = pair[0];
         let low = if pair.len() > 1 { pair[1] } else { 0 };
 
@@ -1056,7 +1259,11 @@ fn encode_addr_block(called: &X121Addr, calling: &X121Addr, buf: &mut BytesMut)
     len
 }
 
-fn decode_addr_block(buf: &mut Bytes) -> Result<(X121Addr, X121Addr), String> {
+fn decode_addr_block(buf: &mut Bytes, gfi: u8) -> Result<(X121Addr, X121Addr), String> {
+    if (gfi & GFI_A_BIT) != 0 {
+        return decode_toa_npi_addr_block(buf);
+    }
+
     #[allow(clippy::len_zero)]
     if buf.len() < 1 {
         return Err(format!("addr block too short: {}", buf.len()));
@@ -1067,21 +1274,10 @@ fn decode_addr_block(buf: &mut Bytes) -> Result<(X121Addr, X121Addr), String> {
     let calling_len = ((len & 0xf0) >> 4) as usize;
     let called_len = (len & 0x0f) as usize;
 
-    // Convert the length in digits to the length in packed bytes.
-    let len = called_len + calling_len;
-    let len = (len / 2) + (len % 2);
-
-    if buf.len() < len {
-        return Err(format!("addr block incomplete: {}", buf.len()));
-    }
-
-    let addr_buf = buf.split_to(len);
-
-    // Unpack the digits.
-    let mut digits = addr_buf.iter().flat_map(|b| [(b & 0xf0) >> 4, b & 0x0f]);
+    let mut semi_octets = decode_semi_octets(buf, called_len + calling_len)?;
 
-    let called_digits: Vec<u8> = digits.by_ref().take(called_len).collect();
-    let calling_digits: Vec<u8> = digits.take(calling_len).collect();
+    let called_digits: Vec<u8> = semi_octets.by_ref().take(called_len).collect();
+    let calling_digits: Vec<u8> = semi_octets.take(calling_len).collect();
 
     // Convert the digits to addresses.
     let called = X121Addr::from_digits(&called_digits)?;
@@ -1090,6 +1286,49 @@ fn decode_addr_block(buf: &mut Bytes) -> Result<(X121Addr, X121Addr), String> {
     Ok((called, calling))
 }
 
+fn decode_toa_npi_addr_block(buf: &mut Bytes) -> Result<(X121Addr, X121Addr), String> {
+    if buf.len() < 2 {
+        return Err(format!("addr block too short: {}", buf.len()));
+    }
+
+    let called_len = buf.get_u8() as usize;
+    let calling_len = buf.get_u8() as usize;
+
+    let mut semi_octets = decode_semi_octets(buf, called_len + calling_len)?;
+
+    let called: Vec<u8> = semi_octets.by_ref().take(called_len).collect();
+    let calling: Vec<u8> = semi_octets.take(calling_len).collect();
+
+    Ok((toa_npi_addr(&called)?, toa_npi_addr(&calling)?))
+}
+
+fn toa_npi_addr(semi_octets: &[u8]) -> Result<X121Addr, String> {
+    match semi_octets {
+        [] => Ok(X121Addr::null()),
+        [toa, npi, digits @ ..] => {
+            let addr = X121Addr::from_digits(digits)?;
+
+            Ok(addr.with_type(X121AddrType::from(*toa), X121NumberingPlan::from(*npi)))
+        }
+        _ => Err("addr too short for TOA/NPI".into()),
+    }
+}
+
+/// Splits the number of semi-octets provided from the buffer, returning an
+/// iterator over the semi-octets.
+fn decode_semi_octets(buf: &mut Bytes, len: usize) -> Result<impl Iterator<Item = u8>, String> {
+    // Convert the length in semi-octets to the length in packed bytes.
+    let packed_len = (len / 2) + (len % 2);
+
+    if buf.len() < packed_len {
+        return Err(format!("addr block incomplete: {}", buf.len()));
+    }
+
+    let packed = buf.split_to(packed_len);
+
+    Ok((0..packed_len).flat_map(move |i| [(packed[i] & 0xf0) >> 4, packed[i] & 0x0f]))
+}
+
 fn encode_facilities_block(
     facilities: &[X25Facility],
     buf: &mut BytesMut,
@@ -1233,6 +1472,60 @@ mod tests {
         assert!(call_request.call_user_data.is_empty());
     }
 
+    #[test]
+    fn encode_call_request_with_toa_npi() {
+        let call_request = X25CallRequest {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            called_addr: X121Addr::from_str("1234")
+                .unwrap()
+                .with_type(X121AddrType::International, X121NumberingPlan::E164),
+            calling_addr: X121Addr::from_str("567").unwrap(),
+            facilities: Vec::new(),
+            call_user_data: Bytes::new(),
+        };
+
+        let mut buf = BytesMut::new();
+
+        assert_eq!(call_request.encode(&mut buf), Ok(12));
+
+        assert_eq!(
+            &buf[..],
+            b"\x90\x01\x0b\x06\x05\x11\x12\x34\x03\x56\x70\x00"
+        );
+    }
+
+    #[test]
+    fn decode_call_request_with_toa_npi() {
+        let buf = Bytes::from_static(b"\x90\x01\x0b\x06\x05\x11\x12\x34\x03\x56\x70\x00");
+
+        let X25Packet::CallRequest(call_request) = X25Packet::decode(buf).unwrap() else {
+            panic!("expected call request");
+        };
+
+        assert_eq!(
+            call_request.called_addr,
+            X121Addr::from_str("1234")
+                .unwrap()
+                .with_type(X121AddrType::International, X121NumberingPlan::E164)
+        );
+
+        assert_eq!(
+            call_request.calling_addr,
+            X121Addr::from_str("567")
+                .unwrap()
+                .with_type(X121AddrType::Unknown, X121NumberingPlan::X121)
+        );
+    }
+
+    #[test]
+    fn decode_call_request_with_invalid_toa_npi() {
+        // The called address is too short to include the TOA/NPI.
+        let buf = Bytes::from_static(b"\x90\x01\x0b\x01\x00\x10\x00");
+
+        assert!(X25Packet::decode(buf).is_err());
+    }
+
     #[test]
     fn decode_call_request_with_facilities() {
         let buf =
@@ -2229,7 +2522,6 @@ mod tests {
 
         let packet = X25Packet::decode(buf);
 
-        dbg!(&packet);
         assert!(packet.is_ok());
 
         let packet = packet.unwrap();
@@ -2354,4 +2646,91 @@ mod tests {
         assert_eq!(reset_confirm.modulo, X25Modulo::Normal);
         assert_eq!(reset_confirm.channel, 1);
     }
+
+    #[test]
+    fn encode_interrupt() {
+        let interrupt = X25Interrupt {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            user_data: Bytes::from_static(b"\x01"),
+        };
+
+        let mut buf = BytesMut::new();
+
+        assert_eq!(interrupt.encode(&mut buf), Ok(4));
+
+        assert_eq!(&buf[..], b"\x10\x01\x23\x01");
+    }
+
+    #[test]
+    fn encode_interrupt_with_invalid_user_data() {
+        let mut interrupt = X25Interrupt {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            user_data: Bytes::new(),
+        };
+
+        let mut buf = BytesMut::new();
+
+        assert!(interrupt.encode(&mut buf).is_err());
+
+        interrupt.user_data = Bytes::from_static(&[0; 33]);
+
+        assert!(interrupt.encode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn decode_interrupt() {
+        let buf = Bytes::from_static(b"\x10\x01\x23\x01");
+
+        let packet = X25Packet::decode(buf);
+
+        assert!(packet.is_ok());
+
+        let packet = packet.unwrap();
+
+        assert_eq!(packet.packet_type(), X25PacketType::Interrupt);
+
+        let X25Packet::Interrupt(interrupt) = packet else {
+            unreachable!()
+        };
+
+        assert_eq!(interrupt.modulo, X25Modulo::Normal);
+        assert_eq!(interrupt.channel, 1);
+        assert_eq!(&interrupt.user_data[..], b"\x01");
+    }
+
+    #[test]
+    fn encode_interrupt_confirm() {
+        let interrupt_confirm = X25InterruptConfirm {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+        };
+
+        let mut buf = BytesMut::new();
+
+        assert_eq!(interrupt_confirm.encode(&mut buf), Ok(3));
+
+        assert_eq!(&buf[..], b"\x10\x01\x27");
+    }
+
+    #[test]
+    fn decode_interrupt_confirm() {
+        let buf = Bytes::from_static(b"\x10\x01\x27");
+
+        let packet = X25Packet::decode(buf);
+
+        assert!(packet.is_ok());
+
+        let packet = packet.unwrap();
+
+        assert_eq!(packet.packet_type(), X25PacketType::InterruptConfirm);
+
+        let X25Packet::InterruptConfirm(interrupt_confirm) = packet else {
+            unreachable!()
+        };
+
+        assert_eq!(interrupt_confirm.modulo, X25Modulo::Normal);
+        assert_eq!(interrupt_confirm.channel, 1);
+    }
 }
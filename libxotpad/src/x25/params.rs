@@ -2,6 +2,8 @@
 //!
 //! This module provides functionalty for managing X.25 parameters.
 
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use std::time::Duration;
 
 use crate::x121::X121Addr;
@@ -28,6 +30,10 @@ pub struct X25Params {
     /// The number of packets that can be received before an acknowledgment is sent.
     pub recv_window_size: u8,
 
+    /// The maximum length of a complete packet sequence received, a longer
+    /// sequence causes the circuit to be reset. `None` for no limit.
+    pub max_recv_sequence_len: Option<usize>,
+
     /// The _call request_ timeout.
     pub t21: Duration,
 
@@ -36,4 +42,123 @@ pub struct X25Params {
 
     /// The _clear request_ timeout.
     pub t23: Duration,
+
+    /// The address format used in call setup and clearing packets.
+    ///
+    /// This is the format used for outgoing calls, for incoming calls the
+    /// format of the _call request_ is used.
+    pub addr_format: X25AddrFormat,
+
+    /// The interval after which an idle circuit is probed, by sending a
+    /// _receive ready_ packet, so that a dead peer is detected by the failure
+    /// of the underlying transport.
+    pub idle_probe: Option<Duration>,
+
+    /// The role of the local party, used to resolve call collisions.
+    pub role: X25Role,
+
+    /// The range of logical channels available for calls.
+    pub channels: RangeInclusive<u16>,
+}
+
+impl X25Params {
+    /// Allocates a logical channel for an outgoing call, returning `None` if all
+    /// channels are in use.
+    ///
+    /// A DTE allocates channels from the top of the range, and a DCE from the
+    /// bottom, to minimize the chance of a call collision.
+    pub fn allocate_channel<F>(&self, is_in_use: F) -> Option<u16>
+    where
+        F: Fn(u16) -> bool,
+    {
+        match self.role {
+            X25Role::Dte => self.channels.clone().rev().find(|&c| !is_in_use(c)),
+            X25Role::Dce => self.channels.clone().find(|&c| !is_in_use(c)),
+        }
+    }
+}
+
+/// Address format used in call setup and clearing packets.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum X25AddrFormat {
+    /// Addresses contain only X.121 digits.
+    #[default]
+    NonToaNpi,
+
+    /// Addresses are prefixed with a type of address and numbering plan
+    /// identification, indicated by the A-bit.
+    ToaNpi,
+}
+
+/// Role of the local party on an X.25 link.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum X25Role {
+    /// Data terminal equipment, on a call collision the incoming call is
+    /// cancelled by the DCE.
+    Dte,
+
+    /// Data circuit-terminating equipment, on a call collision the outgoing call
+    /// is cancelled and the incoming call proceeds.
+    Dce,
+}
+
+impl FromStr for X25Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "dte" => Ok(X25Role::Dte),
+            "dce" => Ok(X25Role::Dce),
+            _ => Err(format!("invalid role: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(role: X25Role) -> X25Params {
+        X25Params {
+            addr: X121Addr::null(),
+            modulo: X25Modulo::Normal,
+            send_packet_size: 128,
+            send_window_size: 2,
+            recv_packet_size: 128,
+            recv_window_size: 2,
+            max_recv_sequence_len: None,
+            t21: Duration::from_secs(200),
+            t22: Duration::from_secs(180),
+            t23: Duration::from_secs(180),
+            addr_format: X25AddrFormat::NonToaNpi,
+            idle_probe: None,
+            role,
+            channels: 1..=4,
+        }
+    }
+
+    #[test]
+    fn allocate_channel_dte() {
+        let params = params(X25Role::Dte);
+
+        assert_eq!(params.allocate_channel(|_| false), Some(4));
+        assert_eq!(params.allocate_channel(|c| c >= 3), Some(2));
+        assert_eq!(params.allocate_channel(|_| true), None);
+    }
+
+    #[test]
+    fn allocate_channel_dce() {
+        let params = params(X25Role::Dce);
+
+        assert_eq!(params.allocate_channel(|_| false), Some(1));
+        assert_eq!(params.allocate_channel(|c| c <= 2), Some(3));
+        assert_eq!(params.allocate_channel(|_| true), None);
+    }
+
+    #[test]
+    fn role_from_str() {
+        assert_eq!(X25Role::from_str("dte"), Ok(X25Role::Dte));
+        assert_eq!(X25Role::from_str("DCE"), Ok(X25Role::Dce));
+        assert!(X25Role::from_str("other").is_err());
+    }
 }
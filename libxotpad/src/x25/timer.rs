@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// X.25 timer, for the DTE.
+///
+/// When a timer expires the error returned to the application has the kind
+/// `io::ErrorKind::TimedOut` and contains the `X25Timer` that expired.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum X25Timer {
+    /// The _call request_ timer, the call was cleared.
+    T21,
+
+    /// The _reset request_ timer, the call was cleared after the reset was
+    /// retransmitted.
+    T22,
+
+    /// The _clear request_ timer, the channel was declared out of order after
+    /// the clear was retransmitted.
+    T23,
+}
+
+impl X25Timer {
+    /// Returns the `X25Timer` that expired, if the error is the result of a timer
+    /// expiry.
+    pub fn from_error(err: &io::Error) -> Option<X25Timer> {
+        if err.kind() != io::ErrorKind::TimedOut {
+            return None;
+        }
+
+        err.get_ref()?.downcast_ref::<X25Timer>().copied()
+    }
+}
+
+impl fmt::Display for X25Timer {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            X25Timer::T21 => write!(fmt, "call request timer (T21) expired"),
+            X25Timer::T22 => write!(fmt, "reset request timer (T22) expired"),
+            X25Timer::T23 => write!(fmt, "clear request timer (T23) expired"),
+        }
+    }
+}
+
+impl Error for X25Timer {}
+
+impl From<X25Timer> for io::Error {
+    fn from(timer: X25Timer) -> Self {
+        io::Error::new(io::ErrorKind::TimedOut, timer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_error() {
+        let err = io::Error::from(X25Timer::T22);
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(X25Timer::from_error(&err), Some(X25Timer::T22));
+
+        let err = io::Error::from(io::ErrorKind::TimedOut);
+
+        assert_eq!(X25Timer::from_error(&err), None);
+
+        let err = io::Error::other(X25Timer::T21);
+
+        assert_eq!(X25Timer::from_error(&err), None);
+    }
+}
@@ -5,21 +5,29 @@
 use bytes::{BufMut, Bytes, BytesMut};
 use std::cmp::min;
 use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
 use std::io;
-use std::sync::{Arc, Barrier};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Barrier, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant};
+use tracing::{debug, info, info_span, warn, Span};
 use tracing_mutex::stdsync::{Condvar, Mutex, RwLock};
 
-use crate::x121::X121Addr;
+use crate::clock::Clock;
+use crate::x121::{X121Addr, X121AddrType, X121NumberingPlan};
 use crate::x25::facility::X25Facility;
 use crate::x25::packet::{
-    X25CallAccept, X25CallRequest, X25ClearConfirm, X25ClearRequest, X25Data, X25Packet,
-    X25ReceiveReady, X25ResetConfirm, X25ResetRequest,
+    X25CallAccept, X25CallRequest, X25ClearConfirm, X25ClearRequest, X25Data, X25Interrupt,
+    X25InterruptConfirm, X25Packet, X25ReceiveNotReady, X25ReceiveReady, X25ResetConfirm,
+    X25ResetRequest,
 };
-use crate::x25::params::X25Params;
+use crate::x25::params::{X25AddrFormat, X25Params, X25Role};
 use crate::x25::seq::{next_seq, Window, X25Modulo};
-use crate::xot::XotLink;
+use crate::x25::timer::X25Timer;
+use crate::xot::{XotLink, XotLinkStats};
 
 /// X.25 virtual circuit.
 pub trait Vc {
@@ -36,19 +44,110 @@ pub trait Vc {
     fn is_connected(&self) -> bool;
 }
 
+/// Event on an X.25 virtual circuit.
+#[derive(Clone, Debug)]
+pub enum VcEvent {
+    /// The call was connected, only received when subscribed before the call
+    /// is connected, see `Svc::call_with_events` and
+    /// `SvcIncomingCall::subscribe`.
+    Connected,
+
+    /// The circuit was reset by the remote party, data in transit may have
+    /// been lost.
+    Reset { cause_code: u8, diagnostic_code: u8 },
+
+    /// An _interrupt_ was received, with the interrupt user data.
+    Interrupt(Bytes),
+
+    /// Sending is blocked, either by a closed window or the remote party being
+    /// busy.
+    FlowBlocked,
+
+    /// Sending is no longer blocked.
+    FlowUnblocked,
+
+    /// The call was cleared by the remote party.
+    Cleared { cause_code: u8, diagnostic_code: u8 },
+
+    /// A timer expired.
+    TimerExpired(X25Timer),
+
+    /// The link is out of order, with the reason.
+    OutOfOrder(String),
+}
+
+/// X.25 virtual circuit statistics.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct SvcStats {
+    /// The number of packets sent.
+    pub packets_sent: u64,
+
+    /// The number of packets received.
+    pub packets_received: u64,
+
+    /// The number of bytes sent, including packet headers.
+    pub bytes_sent: u64,
+
+    /// The number of bytes received, including packet headers.
+    pub bytes_received: u64,
+
+    /// The number of _data_ packets sent.
+    pub data_packets_sent: u64,
+
+    /// The number of _data_ packets received.
+    pub data_packets_received: u64,
+
+    /// The number of packets retransmitted on timer expiry.
+    ///
+    /// Only _reset_ and _clear requests_ are retransmitted, _data_ packets are
+    /// not retransmitted as _reject_ is not supported.
+    pub packets_retransmitted: u64,
+
+    /// The number of _receive ready_ packets sent.
+    pub rr_sent: u64,
+
+    /// The number of _receive ready_ packets received.
+    pub rr_received: u64,
+
+    /// The number of _receive not ready_ packets sent.
+    pub rnr_sent: u64,
+
+    /// The number of _receive not ready_ packets received.
+    pub rnr_received: u64,
+
+    /// The number of resets initiated locally.
+    pub resets_sent: u64,
+
+    /// The number of resets initiated by the remote party.
+    pub resets_received: u64,
+
+    /// The total time sending was blocked by flow control.
+    pub blocked_time: Duration,
+
+    /// The time between sending the _call request_ and receiving the _call
+    /// accept_, only available for outgoing calls.
+    pub call_setup_time: Option<Duration>,
+}
+
+#[derive(Default, Debug)]
+struct StatsState {
+    stats: SvcStats,
+    blocked_since: Option<Instant>,
+}
+
 #[derive(Debug)]
 enum VcState {
     Ready,
     WaitCallAccept(Instant),
     DataTransfer(DataTransferState),
-    WaitResetConfirm(Instant),
-    WaitClearConfirm(Instant, ClearInitiator),
+    WaitResetConfirm(PendingRequest<X25ResetRequest>),
+    WaitClearConfirm(PendingRequest<X25ClearRequest>, ClearInitiator),
 
     // These are our custom ones...
     Called(X25CallRequest),
     #[allow(dead_code)] // TODO
     Cleared(ClearInitiator, Option<X25ClearConfirm>),
-    OutOfOrder,
+    OutOfOrder(OutOfOrderCause),
 }
 
 impl VcState {
@@ -58,6 +157,19 @@ impl VcState {
             VcState::DataTransfer(_) | VcState::WaitResetConfirm(_)
         )
     }
+
+    fn name(&self) -> &'static str {
+        match self {
+            VcState::Ready => "ready",
+            VcState::WaitCallAccept(_) => "wait_call_accept",
+            VcState::DataTransfer(_) => "data_transfer",
+            VcState::WaitResetConfirm(_) => "wait_reset_confirm",
+            VcState::WaitClearConfirm(_, _) => "wait_clear_confirm",
+            VcState::Called(_) => "called",
+            VcState::Cleared(_, _) => "cleared",
+            VcState::OutOfOrder(_) => "out_of_order",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -65,28 +177,115 @@ struct DataTransferState {
     modulo: X25Modulo,
     send_window: Window,
     recv_seq: u8,
+    idle_since: Instant,
+    is_remote_busy: bool,
+    is_local_busy: bool,
+    is_blocked: bool,
+    is_interrupt_pending: bool,
+
+    /// The qualifier and length of the incomplete packet sequence being
+    /// received.
+    recv_sequence: Option<(bool, usize)>,
+}
+
+/// Request awaiting confirmation, that may be retransmitted on timer expiry.
+#[derive(Debug)]
+struct PendingRequest<P> {
+    packet: P,
+    sent_at: Instant,
+    retransmit_count: u8,
 }
 
 #[derive(Clone, Debug)]
 enum ClearInitiator {
     Local,
     Remote(X25ClearRequest),
-    #[allow(dead_code)] // TODO
-    TimeOut(u8),
+    TimeOut(X25Timer),
+}
+
+#[derive(Debug)]
+enum OutOfOrderCause {
+    Link(io::ErrorKind, String),
+    Timer(X25Timer),
+}
+
+impl OutOfOrderCause {
+    fn to_error(&self) -> io::Error {
+        match self {
+            OutOfOrderCause::Link(kind, msg) => {
+                io::Error::new(*kind, format!("link is out of order: {msg}"))
+            }
+            OutOfOrderCause::Timer(timer) => io::Error::from(*timer),
+        }
+    }
+}
+
+impl From<&io::Error> for OutOfOrderCause {
+    fn from(err: &io::Error) -> Self {
+        match X25Timer::from_error(err) {
+            Some(timer) => OutOfOrderCause::Timer(timer),
+            None => OutOfOrderCause::Link(err.kind(), err.to_string()),
+        }
+    }
 }
 
+/// The number of received packets waiting to be consumed, before the remote
+/// party is told to stop sending with a _receive not ready_ packet.
+const RECV_QUEUE_LIMIT: usize = 32;
+
+/// The number of times a _reset request_ is retransmitted on T22 expiry, before
+/// the call is cleared.
+const R22_RETRANSMIT_LIMIT: u8 = 1;
+
+/// The number of times a _clear request_ is retransmitted on T23 expiry, before
+/// the channel is declared out of order.
+const R23_RETRANSMIT_LIMIT: u8 = 1;
+
 /// X.25 _switched_ virtual circuit, or _virtual call_.
 pub struct Svc(Arc<VcInner>);
 
 impl Svc {
+    /// Makes a call, using the clock provided for timers.
     pub fn call(
         link: XotLink,
         channel: u16,
         addr: &X121Addr,
         call_user_data: &[u8],
         params: &X25Params,
+        clock: Arc<dyn Clock>,
+    ) -> io::Result<Self> {
+        Svc::new(link, channel, params, clock).make_call(addr, call_user_data, params)
+    }
+
+    /// Makes a call, as with `call`, sending events on this circuit to the
+    /// `Sender` provided.
+    ///
+    /// Unlike `subscribe`, the events that occur during call setup are sent,
+    /// such as `VcEvent::Connected` or the expiry of T21.
+    pub fn call_with_events(
+        link: XotLink,
+        channel: u16,
+        addr: &X121Addr,
+        call_user_data: &[u8],
+        params: &X25Params,
+        clock: Arc<dyn Clock>,
+        events: Sender<VcEvent>,
+    ) -> io::Result<Self> {
+        let svc = Svc::new(link, channel, params, clock);
+
+        svc.0.subscribers.lock().unwrap().push(events);
+
+        svc.make_call(addr, call_user_data, params)
+    }
+
+    fn make_call(
+        self,
+        addr: &X121Addr,
+        call_user_data: &[u8],
+        params: &X25Params,
     ) -> io::Result<Self> {
-        let svc = Svc::new(link, channel, params);
+        let channel = self.0.channel();
+        let svc = self;
 
         {
             let inner = &svc.0;
@@ -99,20 +298,28 @@ impl Svc {
                     todo!("invalid state");
                 }
 
+                inner.set_call_span(addr, &params.addr);
+
                 let call_request = create_call_request(channel, addr, call_user_data, params);
 
+                // T21 is started before sending, so that a reply can never
+                // precede the start time.
+                let sent_at = inner.clock.now();
+
                 if let Err(err) = inner.send_packet(&call_request.into()) {
+                    let out_of_order_err = OutOfOrderCause::from(&err).to_error();
+
                     inner.out_of_order(&mut state, err);
-                    inner.engine_wait.notify_all();
-                    return Err(io::Error::other("link is out of order"));
+                    drop(state);
+                    inner.wake_engine();
+                    return Err(out_of_order_err);
                 }
 
-                let next_state = VcState::WaitCallAccept(Instant::now());
-
-                inner.change_state(&mut state, next_state);
-                inner.engine_wait.notify_all();
+                inner.change_state(&mut state, VcState::WaitCallAccept(sent_at));
             }
 
+            inner.wake_engine();
+
             // Wait for the result.
             let mut state = inner.state.0.lock().unwrap();
 
@@ -137,11 +344,16 @@ impl Svc {
                         let msg = format!("C:{cause_code} D:{diagnostic_code}");
                         return Err(io::Error::new(io::ErrorKind::ConnectionReset, msg));
                     }
-                    VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(_))
-                    | VcState::Cleared(ClearInitiator::TimeOut(_), _) => {
-                        return Err(io::Error::from(io::ErrorKind::TimedOut));
+                    VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(timer))
+                    | VcState::Cleared(ClearInitiator::TimeOut(timer), _) => {
+                        return Err(timer.into());
+                    }
+                    VcState::Called(ref call_request) => {
+                        let incoming_call = SvcIncomingCall(svc.clone(), call_request.clone());
+
+                        return Err(io::Error::other(SvcCallCollision(incoming_call)));
                     }
-                    VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
+                    VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
                     _ => panic!("unexpected state"),
                 }
             }
@@ -150,49 +362,47 @@ impl Svc {
         Ok(svc)
     }
 
+    /// Waits for an incoming call, for up to the duration provided as measured
+    /// by the clock.
     pub fn listen_timeout(
         link: XotLink,
         channel: u16,
         params: &X25Params,
+        clock: Arc<dyn Clock>,
         duration: Duration,
     ) -> io::Result<SvcIncomingCall> {
-        let svc = Svc::new(link, channel, params);
+        let svc = Svc::new(link, channel, params, clock);
 
         let call_request = {
             let inner = &svc.0;
 
             let mut state = inner.state.0.lock().unwrap();
 
-            let mut remaining_duration = duration;
+            let start = inner.clock.now();
 
+            // The clock waker notifies the state condition, so that the timeout
+            // is checked again when the clock is advanced.
             while matches!(*state, VcState::Ready) {
-                let start = Instant::now();
-
-                let result = inner
-                    .state
-                    .1
-                    .wait_timeout(state, remaining_duration)
-                    .unwrap();
-
-                state = result.0;
-
-                if !matches!(*state, VcState::Ready) {
-                    break;
-                }
+                let remaining_duration = duration.saturating_sub(inner.clock.elapsed(start));
 
-                remaining_duration = remaining_duration.saturating_sub(start.elapsed());
-
-                if result.1.timed_out() || remaining_duration.is_zero() {
+                if remaining_duration.is_zero() {
                     // TODO: See note below about returning XotLink to the caller...
                     let _ = inner.send_link.lock().unwrap().shutdown();
 
                     return Err(io::Error::from(io::ErrorKind::TimedOut));
                 }
+
+                state = inner
+                    .state
+                    .1
+                    .wait_timeout(state, remaining_duration)
+                    .unwrap()
+                    .0;
             }
 
             match *state {
                 VcState::Called(ref call_request) => call_request.clone(),
-                VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
+                VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
                 _ => panic!("unexpected state"),
             }
         };
@@ -220,9 +430,10 @@ impl Svc {
                     diagnostic_code,
                     ClearInitiator::Local,
                 );
-                inner.engine_wait.notify_all();
             }
 
+            inner.wake_engine();
+
             // Wait for the result.
             let mut state = inner.state.0.lock().unwrap();
 
@@ -232,7 +443,7 @@ impl Svc {
 
             match *state {
                 VcState::Cleared(ClearInitiator::Local, _) => { /* This is the expected state */ }
-                VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
+                VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
                 _ => panic!("unexpected state"),
             }
         }
@@ -258,6 +469,158 @@ impl Svc {
         Ok(())
     }
 
+    /// Sends an _interrupt_ with the user data provided, which is not subject to
+    /// flow control.
+    ///
+    /// Only one interrupt may be outstanding, a `WouldBlock` error is returned
+    /// if the previous interrupt has not been confirmed.
+    pub fn interrupt(&self, user_data: &[u8]) -> io::Result<()> {
+        let inner = &self.0;
+
+        if user_data.is_empty() || user_data.len() > 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid interrupt user data length",
+            ));
+        }
+
+        let mut state = inner.state.0.lock().unwrap();
+
+        let VcState::DataTransfer(ref data_transfer_state) = *state else {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        };
+
+        if data_transfer_state.is_interrupt_pending {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "interrupt not confirmed",
+            ));
+        }
+
+        let interrupt = X25Interrupt {
+            modulo: inner.params.read().unwrap().modulo,
+            channel: inner.channel(),
+            user_data: Bytes::copy_from_slice(user_data),
+        };
+
+        if let Err(err) = inner.send_packet(&interrupt.into()) {
+            inner.out_of_order(&mut state, err);
+
+            let VcState::OutOfOrder(ref cause) = *state else {
+                panic!("unexpected state");
+            };
+
+            return Err(cause.to_error());
+        }
+
+        if let VcState::DataTransfer(ref mut data_transfer_state) = *state {
+            data_transfer_state.is_interrupt_pending = true;
+        }
+
+        Ok(())
+    }
+
+    /// Receives the user data of a single _data_ packet, without reassembling a
+    /// complete packet sequence, returning the user data, the qualifier and the
+    /// more data indicator.
+    pub fn recv_packet(&self) -> io::Result<Option<(Bytes, bool, bool)>> {
+        self.recv_with(|queue| {
+            let data = queue.pop_front()?;
+
+            Some((data.user_data, data.qualifier, data.more))
+        })
+    }
+
+    fn recv_with<T, F>(&self, pop: F) -> io::Result<Option<T>>
+    where
+        F: Fn(&mut VecDeque<X25Data>) -> Option<T>,
+    {
+        let inner = &self.0;
+
+        // TODO: introduce another outer "recv" lock, maybe, but for now...
+
+        loop {
+            // NOTE: state and recv_data_queue lock acquisition order is important
+            // to avoid deadlock.
+            let mut state = inner.state.0.lock().unwrap();
+
+            let mut queue = inner.recv_data_queue.0.lock().unwrap();
+
+            if let Some(data) = pop(&mut queue) {
+                let is_local_ready = is_local_ready(&queue);
+
+                drop(queue);
+
+                // The remote party can resume sending, now that the data has
+                // been consumed.
+                if let VcState::DataTransfer(ref mut data_transfer_state) = *state {
+                    if data_transfer_state.is_local_busy && is_local_ready {
+                        data_transfer_state.is_local_busy = false;
+
+                        inner.receive_ready(&mut state);
+                    }
+                }
+
+                return Ok(Some(data));
+            }
+
+            if !state.is_connected() {
+                match *state {
+                    VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(timer))
+                    | VcState::Cleared(ClearInitiator::TimeOut(timer), _) => {
+                        return Err(timer.into());
+                    }
+                    VcState::WaitClearConfirm(_, _) | VcState::Cleared(_, _) => {
+                        return Ok(None);
+                    }
+                    VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
+                    _ => panic!("unexpected state"),
+                }
+            }
+
+            drop(state);
+
+            // drop the lock on the queue, we'll reaquire above to maintain
+            // acquisition order
+            drop(inner.recv_data_queue.1.wait(queue).unwrap());
+        }
+    }
+
+    /// Returns the tracing span for this circuit.
+    pub(crate) fn span(&self) -> &Span {
+        self.0.span()
+    }
+
+    /// Subscribes to events on this circuit.
+    ///
+    /// Only events that occur after subscribing are received, the subscription
+    /// ends when the `Receiver` is dropped.
+    pub fn subscribe(&self) -> Receiver<VcEvent> {
+        let (tx, rx) = channel();
+
+        self.0.subscribers.lock().unwrap().push(tx);
+
+        rx
+    }
+
+    /// Returns the statistics for this circuit.
+    pub fn stats(&self) -> SvcStats {
+        let state = self.0.stats.lock().unwrap();
+
+        let mut stats = state.stats;
+
+        if let Some(blocked_since) = state.blocked_since {
+            stats.blocked_time += self.0.clock.elapsed(blocked_since);
+        }
+
+        stats
+    }
+
+    /// Returns the statistics for the underlying XOT link.
+    pub fn link_stats(&self) -> XotLinkStats {
+        self.0.send_link.lock().unwrap().stats()
+    }
+
     pub fn cleared(&self) -> Option<(u8, u8)> {
         let state = self.0.state.0.lock().unwrap();
 
@@ -269,10 +632,34 @@ impl Svc {
         }
     }
 
-    fn new(link: XotLink, channel: u16, params: &X25Params) -> Self {
+    /// Returns the clock used for timers on this circuit.
+    pub(crate) fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.0.clock)
+    }
+
+    fn new(link: XotLink, channel: u16, params: &X25Params, clock: Arc<dyn Clock>) -> Self {
         let (send_link, recv_link) = split_xot_link(link);
 
-        let inner = Arc::new(VcInner::new(send_link, channel, params));
+        let inner = Arc::new(VcInner::new(send_link, channel, params, clock));
+
+        // Timers are checked again when the clock is advanced, the waker is no
+        // longer required once the circuit is gone.
+        inner.clock.register_waker({
+            let inner = Arc::downgrade(&inner);
+
+            Arc::new(move || {
+                let Some(inner) = inner.upgrade() else {
+                    return false;
+                };
+
+                inner.wake_engine();
+
+                let _state = inner.state.0.lock().unwrap();
+                inner.state.1.notify_all();
+
+                true
+            })
+        });
 
         let barrier = Arc::new(Barrier::new(2));
 
@@ -295,12 +682,88 @@ impl Svc {
 /// Incoming X.25 _call_ that can be accepted, or cleared.
 pub struct SvcIncomingCall(Svc, X25CallRequest);
 
+/// Error returned when an outgoing call, made by a DCE, collides with an incoming
+/// call on the same channel.
+///
+/// The outgoing call is cancelled and the incoming call can be accepted, or
+/// cleared, as usual.
+pub struct SvcCallCollision(SvcIncomingCall);
+
+impl SvcCallCollision {
+    /// Returns the `SvcCallCollision` contained in the error, or the original
+    /// error if it is not the result of a call collision.
+    pub fn from_error(err: io::Error) -> Result<Self, io::Error> {
+        if !err.get_ref().is_some_and(|e| e.is::<SvcCallCollision>()) {
+            return Err(err);
+        }
+
+        let inner = err.into_inner().unwrap();
+
+        Ok(*inner.downcast::<SvcCallCollision>().unwrap())
+    }
+
+    pub fn into_incoming_call(self) -> SvcIncomingCall {
+        self.0
+    }
+}
+
+impl fmt::Debug for SvcCallCollision {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("SvcCallCollision")
+            .field(&self.0 .1)
+            .finish()
+    }
+}
+
+impl fmt::Display for SvcCallCollision {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "call collision on channel {}", self.0 .1.channel)
+    }
+}
+
+impl Error for SvcCallCollision {}
+
 impl SvcIncomingCall {
     pub fn request(&self) -> &X25CallRequest {
         &self.1
     }
 
+    /// Subscribes to events on this circuit, as with `Svc::subscribe`.
+    ///
+    /// Subscribing before accepting the call ensures that `VcEvent::Connected`
+    /// is received.
+    pub fn subscribe(&self) -> Receiver<VcEvent> {
+        self.0.subscribe()
+    }
+
     pub fn accept(self) -> io::Result<Svc> {
+        self.accept_with(&X121Addr::null(), b"", &[])
+    }
+
+    /// Accepts the call, with the called address, called user data and
+    /// facilities provided.
+    ///
+    /// The facilities are sent in addition to the negotiated packet and window
+    /// size facilities, for example a _called line address modified
+    /// notification_ or a _throughput class_ negotiated downwards.
+    ///
+    /// If the call accept is invalid the call is cleared, rather than leaving
+    /// the remote party waiting, and an error is returned.
+    pub fn accept_with(
+        self,
+        called_addr: &X121Addr,
+        called_user_data: &[u8],
+        facilities: &[X25Facility],
+    ) -> io::Result<Svc> {
+        let call_accept = match self.call_accept(called_addr, called_user_data, facilities) {
+            Ok(call_accept) => call_accept,
+            Err(err) => {
+                let _ = self.clear(0, 0);
+
+                return Err(err);
+            }
+        };
+
         let svc = self.0;
 
         {
@@ -314,22 +777,61 @@ impl SvcIncomingCall {
                 ));
             }
 
-            let call_accept = create_call_accept(inner.channel, &inner.params.read().unwrap());
-
             if let Err(err) = inner.send_packet(&call_accept.into()) {
+                let out_of_order_err = OutOfOrderCause::from(&err).to_error();
+
                 inner.out_of_order(&mut state, err);
-                inner.engine_wait.notify_all();
+                drop(state);
+                inner.wake_engine();
 
-                return Err(io::Error::other("link is out of order"));
+                return Err(out_of_order_err);
             }
 
             inner.data_transfer(&mut state);
-            inner.engine_wait.notify_all();
+            inner.emit(VcEvent::Connected);
+            drop(state);
+            inner.wake_engine();
         }
 
         Ok(svc)
     }
 
+    fn call_accept(
+        &self,
+        called_addr: &X121Addr,
+        called_user_data: &[u8],
+        facilities: &[X25Facility],
+    ) -> io::Result<X25CallAccept> {
+        if facilities.iter().any(|f| {
+            matches!(
+                f,
+                X25Facility::PacketSize { .. } | X25Facility::WindowSize { .. }
+            )
+        }) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "packet and window size facilities are negotiated",
+            ));
+        }
+
+        let inner = &self.0 .0;
+        let params = inner.params.read().unwrap();
+
+        let mut call_accept = create_call_accept(inner.channel(), &params);
+
+        call_accept.called_addr = format_addr(called_addr, params.addr_format);
+        call_accept.facilities.extend_from_slice(facilities);
+        call_accept.called_user_data = Bytes::copy_from_slice(called_user_data);
+
+        // An invalid call accept is reported to the caller, rather than
+        // leaving the link out of order.
+        call_accept
+            .encode(&mut BytesMut::new())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        Ok(call_accept)
+    }
+
     pub fn clear(self, cause_code: u8, diagnostic_code: u8) -> io::Result<()> {
         let inner = self.0 .0;
 
@@ -343,7 +845,7 @@ impl SvcIncomingCall {
 
         let clear_request = X25ClearRequest {
             modulo: inner.params.read().unwrap().modulo,
-            channel: inner.channel,
+            channel: inner.channel(),
             cause_code,
             diagnostic_code,
             called_addr: X121Addr::null(),
@@ -353,14 +855,18 @@ impl SvcIncomingCall {
         };
 
         if let Err(err) = inner.send_packet(&clear_request.into()) {
+            let out_of_order_err = OutOfOrderCause::from(&err).to_error();
+
             inner.out_of_order(&mut state, err);
-            inner.engine_wait.notify_all();
+            drop(state);
+            inner.wake_engine();
 
-            return Err(io::Error::other("link is out of order"));
+            return Err(out_of_order_err);
         }
 
         inner.cleared(&mut state, ClearInitiator::Local, None);
-        inner.engine_wait.notify_all();
+        drop(state);
+        inner.wake_engine();
 
         Ok(())
     }
@@ -407,40 +913,7 @@ impl Vc for Svc {
     }
 
     fn recv(&self) -> io::Result<Option<(Bytes, bool)>> {
-        let inner = &self.0;
-
-        // TODO: introduce another outer "recv" lock, maybe, but for now...
-
-        loop {
-            // NOTE: state and recv_data_queue lock acquisition order is important
-            // to avoid deadlock.
-            let state = inner.state.0.lock().unwrap();
-
-            let mut queue = inner.recv_data_queue.0.lock().unwrap();
-
-            if let Some(data) = pop_complete_data(&mut queue) {
-                return Ok(Some(data));
-            }
-
-            if !state.is_connected() {
-                match *state {
-                    VcState::Cleared(ClearInitiator::Local | ClearInitiator::Remote(_), _) => {
-                        return Ok(None);
-                    }
-                    VcState::Cleared(ClearInitiator::TimeOut(_), _) => {
-                        return Err(io::Error::from(io::ErrorKind::TimedOut));
-                    }
-                    VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
-                    _ => panic!("unexpected state"),
-                }
-            }
-
-            drop(state);
-
-            // drop the lock on the queue, we'll reaquire above to maintain
-            // acquisition order
-            drop(inner.recv_data_queue.1.wait(queue).unwrap());
-        }
+        self.recv_with(pop_complete_data)
     }
 
     fn reset(&self, cause_code: u8, diagnostic_code: u8) -> io::Result<()> {
@@ -450,15 +923,22 @@ impl Vc for Svc {
         {
             let mut state = inner.state.0.lock().unwrap();
 
-            if !matches!(*state, VcState::DataTransfer(_)) {
-                // TODO: what states is this valid in?
-                todo!("invalid state");
+            match *state {
+                VcState::DataTransfer(_) => {}
+                VcState::WaitResetConfirm(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WouldBlock,
+                        "reset not confirmed",
+                    ));
+                }
+                _ => return Err(io::Error::from(io::ErrorKind::BrokenPipe)),
             }
 
             inner.reset_request(&mut state, cause_code, diagnostic_code);
-            inner.engine_wait.notify_all();
         }
 
+        inner.wake_engine();
+
         // Wait for the result.
         let mut state = inner.state.0.lock().unwrap();
 
@@ -468,11 +948,13 @@ impl Vc for Svc {
 
         match *state {
             VcState::DataTransfer(_) => { /* This is the expected state */ }
-            VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(_))
-            | VcState::Cleared(ClearInitiator::TimeOut(_), _) => {
-                return Err(io::Error::from(io::ErrorKind::TimedOut))
+            VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(timer))
+            | VcState::Cleared(ClearInitiator::TimeOut(timer), _) => return Err(timer.into()),
+            VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
+            // The call was cleared while waiting for the confirmation.
+            VcState::WaitClearConfirm(_, _) | VcState::Cleared(_, _) => {
+                return Err(io::Error::from(io::ErrorKind::BrokenPipe));
             }
-            VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
             _ => panic!("unexpected state"),
         };
 
@@ -495,14 +977,15 @@ impl Vc for Svc {
 
             if !state.is_connected() {
                 match *state {
-                    VcState::Cleared(ClearInitiator::Local | ClearInitiator::Remote(_), _) => {
+                    VcState::WaitClearConfirm(_, ClearInitiator::TimeOut(timer))
+                    | VcState::Cleared(ClearInitiator::TimeOut(timer), _) => {
+                        return Err(timer.into());
+                    }
+                    VcState::WaitClearConfirm(_, _) | VcState::Cleared(_, _) => {
                         // TODO: is this the correct error?
                         return Err(io::Error::from(io::ErrorKind::BrokenPipe));
                     }
-                    VcState::Cleared(ClearInitiator::TimeOut(_), _) => {
-                        return Err(io::Error::from(io::ErrorKind::TimedOut));
-                    }
-                    VcState::OutOfOrder => return Err(io::Error::other("link is out of order")),
+                    VcState::OutOfOrder(ref cause) => return Err(cause.to_error()),
                     _ => panic!("unexpected state"),
                 }
             }
@@ -536,12 +1019,18 @@ impl Clone for Svc {
 
 struct VcInner {
     send_link: Arc<Mutex<XotLink>>,
+    engine_queue: Arc<Mutex<VecDeque<io::Result<Bytes>>>>,
     engine_wait: Arc<Condvar>,
-    channel: u16,
+    channel: AtomicU16,
     state: Arc<(Mutex<VcState>, Condvar)>,
     params: Arc<RwLock<X25Params>>,
     send_data_queue: Arc<(Mutex<VecDeque<SendData>>, Condvar)>,
     recv_data_queue: Arc<(Mutex<VecDeque<X25Data>>, Condvar)>,
+    span: Span,
+    call_span: OnceLock<Span>,
+    subscribers: Mutex<Vec<Sender<VcEvent>>>,
+    stats: Mutex<StatsState>,
+    clock: Arc<dyn Clock>,
 }
 
 struct SendData {
@@ -551,26 +1040,55 @@ struct SendData {
 }
 
 impl VcInner {
-    fn new(send_link: XotLink, channel: u16, params: &X25Params) -> Self {
+    fn new(send_link: XotLink, channel: u16, params: &X25Params, clock: Arc<dyn Clock>) -> Self {
         let state = VcState::Ready;
 
+        let span = info_span!(parent: send_link.span(), "x25_vc", channel);
+
         VcInner {
             send_link: Arc::new(Mutex::new(send_link)),
+            engine_queue: Arc::new(Mutex::new(VecDeque::new())),
             engine_wait: Arc::new(Condvar::new()),
-            channel,
+            channel: AtomicU16::new(channel),
             state: Arc::new((Mutex::new(state), Condvar::new())),
             params: Arc::new(RwLock::new(params.clone())),
             send_data_queue: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
             recv_data_queue: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
+            span,
+            call_span: OnceLock::new(),
+            subscribers: Mutex::new(Vec::new()),
+            stats: Mutex::new(StatsState::default()),
+            clock,
         }
     }
 
-    fn run(&self, mut recv_link: XotLink, barrier: &Arc<Barrier>) {
-        // Create another thread that reads packets, this allows the main loop
-        // wait to be interrupted while the XOT socket read is blocked.
-        let recv_queue = Arc::new(Mutex::new(VecDeque::<io::Result<Bytes>>::new()));
+    fn channel(&self) -> u16 {
+        self.channel.load(Ordering::Relaxed)
+    }
 
-        thread::Builder::new()
+    /// Returns the span for the current call, or the span for the channel if
+    /// there is no call yet.
+    fn span(&self) -> &Span {
+        self.call_span.get().unwrap_or(&self.span)
+    }
+
+    fn set_call_span(&self, called_addr: &X121Addr, calling_addr: &X121Addr) {
+        let span = info_span!(
+            parent: &self.span,
+            "x25_call",
+            called = %called_addr,
+            calling = %calling_addr
+        );
+
+        let _ = self.call_span.set(span);
+    }
+
+    fn run(&self, mut recv_link: XotLink, barrier: &Arc<Barrier>) {
+        // Create another thread that reads packets, this allows the main loop
+        // wait to be interrupted while the XOT socket read is blocked.
+        let recv_queue = Arc::clone(&self.engine_queue);
+
+        thread::Builder::new()
             .name("x25_vc_2".to_string())
             .spawn({
                 let recv_queue = Arc::clone(&recv_queue);
@@ -596,7 +1114,9 @@ impl VcInner {
         let mut recv_queue = recv_queue.lock().unwrap();
 
         loop {
-            let mut timeout = Duration::from_secs(100_000); // TODO
+            // The timeout is set by the current state, if there are no timers
+            // running then wait until a packet is received.
+            let mut timeout = None;
 
             let packet = recv_queue.pop_front();
 
@@ -613,16 +1133,25 @@ impl VcInner {
             };
 
             // Decode the packet.
-            let temp_packet = packet.clone(); // TODO: temporary logging
+            let buf = packet.clone();
             let packet = match packet.map(X25Packet::decode).transpose() {
                 Ok(packet) => packet,
                 Err(err) => {
-                    dbg!(err);
-                    dbg!(temp_packet);
+                    warn!(parent: self.span(), error = %err, packet = ?buf, "packet decode error");
                     todo!("handle packet decode error");
                 }
             };
 
+            if let (Some(ref packet), Some(ref buf)) = (&packet, &buf) {
+                debug!(parent: self.span(), ?packet, "packet received");
+
+                self.stats
+                    .lock()
+                    .unwrap()
+                    .stats
+                    .record_received(packet, buf.len());
+            }
+
             // Validate the packet.
             if let Some(ref _packet) = packet {
                 // TODO...
@@ -635,7 +1164,7 @@ impl VcInner {
                 self.handle_in_packet(packet, &mut state, &mut timeout);
 
                 // Exit loop if we are in a terminal state.
-                if matches!(*state, VcState::Cleared(_, _) | VcState::OutOfOrder) {
+                if matches!(*state, VcState::Cleared(_, _) | VcState::OutOfOrder(_)) {
                     break;
                 }
             }
@@ -643,7 +1172,15 @@ impl VcInner {
             // Only wait if the queue is empty, otherwise don't wait as we won't
             // receive a wakeup call.
             if recv_queue.is_empty() {
-                (recv_queue, _) = self.engine_wait.wait_timeout(recv_queue, timeout).unwrap();
+                recv_queue = match timeout {
+                    Some(timeout) => {
+                        self.engine_wait
+                            .wait_timeout(recv_queue, timeout)
+                            .unwrap()
+                            .0
+                    }
+                    None => self.engine_wait.wait(recv_queue).unwrap(),
+                };
             }
         }
     }
@@ -652,11 +1189,17 @@ impl VcInner {
         &self,
         packet: Option<X25Packet>,
         state: &mut VcState,
-        timeout: &mut Duration,
+        timeout: &mut Option<Duration>,
     ) {
         match *state {
             VcState::Ready => {
                 if let Some(X25Packet::CallRequest(call_request)) = packet {
+                    // The channel is chosen by the caller.
+                    self.channel.store(call_request.channel, Ordering::Relaxed);
+                    self.span.record("channel", call_request.channel);
+
+                    self.set_call_span(&call_request.called_addr, &call_request.calling_addr);
+
                     let mut params = self.params.write().unwrap();
 
                     *params = negotiate_called_params(&call_request, &params);
@@ -673,10 +1216,10 @@ impl VcInner {
                 }
             }
             VcState::WaitCallAccept(start_time) => {
-                let elapsed = start_time.elapsed();
+                let elapsed = self.clock.elapsed(start_time);
                 let X25Params { t21, t23, .. } = *self.params.read().unwrap();
 
-                *timeout = t21; // TODO: <- backup
+                *timeout = Some(t21);
 
                 match packet {
                     Some(X25Packet::CallAccept(call_accept)) => {
@@ -686,28 +1229,61 @@ impl VcInner {
                             *params = negotiate_calling_params(&call_accept, &params);
                         }
 
+                        self.stats.lock().unwrap().stats.call_setup_time = Some(elapsed);
+
                         self.data_transfer(state);
+                        self.emit(VcEvent::Connected);
                     }
                     Some(X25Packet::ClearRequest(clear_request)) => {
                         self.cleared(state, ClearInitiator::Remote(clear_request), None);
                     }
+                    Some(X25Packet::CallRequest(call_request))
+                        if call_request.channel == self.channel() =>
+                    {
+                        let role = self.params.read().unwrap().role;
+
+                        match role {
+                            X25Role::Dte => {
+                                // The DCE will cancel the incoming call, and proceed with
+                                // our call request.
+                                debug!(parent: self.span(), "call collision, ignoring incoming call");
+                            }
+                            X25Role::Dce => {
+                                debug!(parent: self.span(), "call collision, cancelling outgoing call");
+
+                                {
+                                    let mut params = self.params.write().unwrap();
+
+                                    *params = negotiate_called_params(&call_request, &params);
+                                }
+
+                                self.change_state(state, VcState::Called(call_request));
+                            }
+                        }
+                    }
                     Some(_) => { /* TODO: Ignore? */ }
                     None if elapsed > t21 => {
-                        println!("T21 timeout, sending clear request...");
+                        warn!(parent: self.span(), "T21 timeout, sending clear request");
+
+                        self.emit(VcEvent::TimerExpired(X25Timer::T21));
 
                         self.clear_request(
                             state,
                             19, // Local procedure error
                             49, // Time expired for incoming call
-                            ClearInitiator::TimeOut(21),
+                            ClearInitiator::TimeOut(X25Timer::T21),
                         );
 
-                        *timeout = t23;
+                        *timeout = Some(t23);
                     }
-                    None => *timeout = t21 - elapsed,
+                    None => *timeout = Some(t21 - elapsed),
                 }
             }
             VcState::DataTransfer(ref mut data_transfer_state) => {
+                if packet.is_some() {
+                    data_transfer_state.idle_since = self.clock.now();
+                }
+
                 match packet {
                     Some(X25Packet::Data(data)) => 'packet: {
                         if !data_transfer_state.update_recv_seq(data.send_seq) {
@@ -728,7 +1304,24 @@ impl VcInner {
                             break 'packet;
                         }
 
-                        // TODO: check for validation errors...
+                        let params = self.params.read().unwrap().clone();
+
+                        if let Err(diagnostic_code) =
+                            data_transfer_state.update_recv_sequence(&data, &params)
+                        {
+                            warn!(parent: self.span(), diagnostic_code, "invalid packet sequence");
+
+                            self.discard_incomplete_recv_data();
+
+                            self.reset_request(
+                                state,
+                                5, // Local procedure error
+                                diagnostic_code,
+                            );
+
+                            break 'packet;
+                        }
+
                         self.queue_recv_data(data);
 
                         let (sent_count, _) = self.send_queued_data(state);
@@ -737,11 +1330,22 @@ impl VcInner {
                             break 'packet;
                         }
 
-                        // TODO: clean all of this up and work out if
-                        // sometimes, we should hold off...
-                        let is_local_ready = true;
+                        let is_local_ready =
+                            is_local_ready(&self.recv_data_queue.0.lock().unwrap());
+
+                        let VcState::DataTransfer(ref mut data_transfer_state) = *state else {
+                            unreachable!();
+                        };
 
-                        if sent_count == 0 && is_local_ready {
+                        // The remote party is told to hold off until the data
+                        // received has been consumed.
+                        if !is_local_ready {
+                            if !data_transfer_state.is_local_busy {
+                                data_transfer_state.is_local_busy = true;
+
+                                self.receive_not_ready(state);
+                            }
+                        } else if sent_count == 0 {
                             self.receive_ready(state);
                         }
                     }
@@ -755,10 +1359,37 @@ impl VcInner {
                             break 'packet;
                         }
 
+                        data_transfer_state.is_remote_busy = false;
+
+                        self.send_queued_data(state);
+                    }
+                    Some(X25Packet::ReceiveNotReady(receive_not_ready)) => 'packet: {
+                        if !data_transfer_state.update_send_window(receive_not_ready.recv_seq) {
+                            self.reset_request(
+                                state, 5, // Local procedure error
+                                2, // Invalid receive sequence
+                            );
+
+                            break 'packet;
+                        }
+
+                        data_transfer_state.is_remote_busy = true;
+
                         self.send_queued_data(state);
                     }
-                    Some(X25Packet::ResetRequest(_)) => {
+                    Some(X25Packet::Interrupt(interrupt)) => {
+                        self.interrupt_confirm(state);
+                        self.emit(VcEvent::Interrupt(interrupt.user_data));
+                    }
+                    Some(X25Packet::InterruptConfirm(_)) => {
+                        data_transfer_state.is_interrupt_pending = false;
+                    }
+                    Some(X25Packet::ResetRequest(reset_request)) => {
                         self.reset_confirm(state);
+                        self.emit(VcEvent::Reset {
+                            cause_code: reset_request.cause_code,
+                            diagnostic_code: reset_request.diagnostic_code,
+                        });
                     }
                     Some(X25Packet::ClearRequest(clear_request)) => {
                         self.clear_confirm(state, clear_request);
@@ -767,46 +1398,73 @@ impl VcInner {
                     Some(_) => { /* TODO: Ignore? */ }
                     None => {}
                 }
+
+                let idle_probe = self.params.read().unwrap().idle_probe;
+
+                if let Some(idle_probe) = idle_probe {
+                    self.probe_if_idle(state, idle_probe, timeout);
+                }
             }
-            VcState::WaitResetConfirm(start_time) => {
-                let elapsed = start_time.elapsed();
+            VcState::WaitResetConfirm(ref mut pending) => {
+                let elapsed = self.clock.elapsed(pending.sent_at);
                 let X25Params { t22, t23, .. } = *self.params.read().unwrap();
 
-                *timeout = t22; // TODO: backup!
+                *timeout = Some(t22);
 
                 match packet {
                     Some(X25Packet::ResetConfirm(_)) => {
                         self.data_transfer(state);
                     }
-                    Some(X25Packet::ResetRequest(_)) => {
+                    Some(X25Packet::ResetRequest(reset_request)) => {
                         self.reset_confirm(state);
+                        self.emit(VcEvent::Reset {
+                            cause_code: reset_request.cause_code,
+                            diagnostic_code: reset_request.diagnostic_code,
+                        });
                     }
                     Some(X25Packet::ClearRequest(clear_request)) => {
                         self.clear_confirm(state, clear_request);
                         self.recv_data_queue.1.notify_all();
                     }
+                    Some(_) => { /* TODO: Ignore? */ }
+                    None if elapsed > t22 && pending.retransmit_count < R22_RETRANSMIT_LIMIT => {
+                        warn!(parent: self.span(), "T22 timeout, retransmitting reset request");
+
+                        self.emit(VcEvent::TimerExpired(X25Timer::T22));
+
+                        pending.sent_at = self.clock.now();
+                        pending.retransmit_count += 1;
+
+                        self.stats.lock().unwrap().stats.packets_retransmitted += 1;
+
+                        let reset_request = pending.packet.clone();
+
+                        if let Err(err) = self.send_packet(&reset_request.into()) {
+                            self.out_of_order(state, err);
+                        }
+                    }
                     None if elapsed > t22 => {
-                        println!("T22 timeout, sending clear request...");
+                        warn!(parent: self.span(), "T22 timeout, sending clear request");
+
+                        self.emit(VcEvent::TimerExpired(X25Timer::T22));
 
                         self.clear_request(
                             state,
                             19, // Local procedure error
                             51, // Time expired for reset request
-                            ClearInitiator::TimeOut(22),
+                            ClearInitiator::TimeOut(X25Timer::T22),
                         );
 
-                        *timeout = t23;
-                    }
-                    None => *timeout = t22 - elapsed,
-                    Some(_) => { /* TODO: Ignore? Or, do you think I need to send a reset request again!!! */
+                        *timeout = Some(t23);
                     }
+                    None => *timeout = Some(t22 - elapsed),
                 }
             }
-            VcState::WaitClearConfirm(start_time, ref initiator) => {
-                let elapsed = start_time.elapsed();
+            VcState::WaitClearConfirm(ref mut pending, ref initiator) => {
+                let elapsed = self.clock.elapsed(pending.sent_at);
                 let t23 = self.params.read().unwrap().t23;
 
-                *timeout = t23;
+                *timeout = Some(t23);
 
                 match packet {
                     Some(X25Packet::ClearConfirm(clear_confirm)) => {
@@ -814,25 +1472,43 @@ impl VcInner {
 
                         self.cleared(state, initiator, Some(clear_confirm));
                     }
-                    Some(X25Packet::ClearRequest(_)) => todo!(),
+                    Some(X25Packet::ClearRequest(_)) => {
+                        // This is a clear collision, the clear is complete and no
+                        // confirmation is expected.
+                        let initiator = initiator.clone();
+
+                        self.cleared(state, initiator, None);
+                        self.recv_data_queue.1.notify_all();
+                    }
                     Some(_) => { /* TODO: Ignore? */ }
+                    None if elapsed > t23 && pending.retransmit_count < R23_RETRANSMIT_LIMIT => {
+                        warn!(parent: self.span(), "T23 timeout, retransmitting clear request");
+
+                        self.emit(VcEvent::TimerExpired(X25Timer::T23));
+
+                        pending.sent_at = self.clock.now();
+                        pending.retransmit_count += 1;
+
+                        self.stats.lock().unwrap().stats.packets_retransmitted += 1;
+
+                        let clear_request = pending.packet.clone();
+
+                        if let Err(err) = self.send_packet(&clear_request.into()) {
+                            self.out_of_order(state, err);
+                        }
+                    }
                     None if elapsed > t23 => {
-                        println!("T23 timeout");
+                        warn!(parent: self.span(), "T23 timeout, channel is out of order");
 
-                        // TODO:
-                        // For a timeout on a "call request timeout" that leads to a clear
-                        // request Cisco sends "time expired for clear indication" twice (2
-                        // retries of THIS state).
-                        //
-                        // what does it do for a user initiated clear?
-                        let err = io::Error::from(io::ErrorKind::TimedOut);
+                        self.emit(VcEvent::TimerExpired(X25Timer::T23));
 
-                        self.out_of_order(state, err);
+                        self.out_of_order(state, X25Timer::T23.into());
+                        self.recv_data_queue.1.notify_all();
                     }
-                    None => *timeout = t23 - elapsed,
+                    None => *timeout = Some(t23 - elapsed),
                 }
             }
-            VcState::Cleared(_, _) | VcState::OutOfOrder => {
+            VcState::Cleared(_, _) | VcState::OutOfOrder(_) => {
                 // Ignore packet, we'll exit the loop below.
             }
         }
@@ -849,8 +1525,17 @@ impl VcInner {
             modulo,
             send_window: Window::new(send_window_size, modulo),
             recv_seq: 0,
+            idle_since: self.clock.now(),
+            is_remote_busy: false,
+            is_local_busy: false,
+            is_blocked: false,
+            is_interrupt_pending: false,
+            recv_sequence: None,
         });
 
+        // A packet sequence interrupted by a reset will never be completed.
+        self.discard_incomplete_recv_data();
+
         self.change_state(state, next_state);
     }
 
@@ -860,15 +1545,30 @@ impl VcInner {
         initiator: ClearInitiator,
         clear_confirm: Option<X25ClearConfirm>,
     ) {
+        if let ClearInitiator::Remote(ref clear_request) = initiator {
+            self.emit(VcEvent::Cleared {
+                cause_code: clear_request.cause_code,
+                diagnostic_code: clear_request.diagnostic_code,
+            });
+        }
+
         let next_state = VcState::Cleared(initiator, clear_confirm);
 
         self.change_state(state, next_state);
     }
 
-    fn out_of_order(&self, state: &mut VcState, _err: io::Error) {
-        let next_state = VcState::OutOfOrder;
+    fn out_of_order(&self, state: &mut VcState, err: io::Error) {
+        warn!(parent: self.span(), error = %err, "link out of order");
+
+        self.emit(VcEvent::OutOfOrder(err.to_string()));
+
+        let next_state = VcState::OutOfOrder(OutOfOrderCause::from(&err));
 
         self.change_state(state, next_state);
+
+        // Nothing more can be sent or received on the link, closing the socket
+        // ensures the receiver thread exits.
+        let _ = self.send_link.lock().unwrap().shutdown();
     }
 
     fn clear_request(
@@ -880,7 +1580,7 @@ impl VcInner {
     ) {
         let clear_request = X25ClearRequest {
             modulo: self.params.read().unwrap().modulo,
-            channel: self.channel,
+            channel: self.channel(),
             cause_code,
             diagnostic_code,
             called_addr: X121Addr::null(),
@@ -889,10 +1589,18 @@ impl VcInner {
             clear_user_data: Bytes::new(),
         };
 
-        if let Err(err) = self.send_packet(&clear_request.into()) {
+        let sent_at = self.clock.now();
+
+        if let Err(err) = self.send_packet(&clear_request.clone().into()) {
             self.out_of_order(state, err);
         } else {
-            let next_state = VcState::WaitClearConfirm(Instant::now(), initiator);
+            let pending = PendingRequest {
+                packet: clear_request,
+                sent_at,
+                retransmit_count: 0,
+            };
+
+            let next_state = VcState::WaitClearConfirm(pending, initiator);
 
             self.change_state(state, next_state);
         }
@@ -901,7 +1609,7 @@ impl VcInner {
     fn clear_confirm(&self, state: &mut VcState, clear_request: X25ClearRequest) {
         let clear_confirm = X25ClearConfirm {
             modulo: self.params.read().unwrap().modulo,
-            channel: self.channel,
+            channel: self.channel(),
             called_addr: X121Addr::null(),
             calling_addr: X121Addr::null(),
             facilities: Vec::new(),
@@ -917,15 +1625,25 @@ impl VcInner {
     fn reset_request(&self, state: &mut VcState, cause_code: u8, diagnostic_code: u8) {
         let reset_request = X25ResetRequest {
             modulo: self.params.read().unwrap().modulo,
-            channel: self.channel,
+            channel: self.channel(),
             cause_code,
             diagnostic_code,
         };
 
-        if let Err(err) = self.send_packet(&reset_request.into()) {
+        let sent_at = self.clock.now();
+
+        if let Err(err) = self.send_packet(&reset_request.clone().into()) {
             self.out_of_order(state, err);
         } else {
-            let next_state = VcState::WaitResetConfirm(Instant::now());
+            self.stats.lock().unwrap().stats.resets_sent += 1;
+
+            let pending = PendingRequest {
+                packet: reset_request,
+                sent_at,
+                retransmit_count: 0,
+            };
+
+            let next_state = VcState::WaitResetConfirm(pending);
 
             self.change_state(state, next_state);
         }
@@ -934,7 +1652,7 @@ impl VcInner {
     fn reset_confirm(&self, state: &mut VcState) {
         let reset_confirm = X25ResetConfirm {
             modulo: self.params.read().unwrap().modulo,
-            channel: self.channel,
+            channel: self.channel(),
         };
 
         if let Err(err) = self.send_packet(&reset_confirm.into()) {
@@ -944,6 +1662,17 @@ impl VcInner {
         }
     }
 
+    fn interrupt_confirm(&self, state: &mut VcState) {
+        let interrupt_confirm = X25InterruptConfirm {
+            modulo: self.params.read().unwrap().modulo,
+            channel: self.channel(),
+        };
+
+        if let Err(err) = self.send_packet(&interrupt_confirm.into()) {
+            self.out_of_order(state, err);
+        }
+    }
+
     fn send_queued_data(&self, state: &mut VcState) -> (usize, usize) {
         let VcState::DataTransfer(ref mut data_transfer_state) = *state else {
             panic!("unexpected state")
@@ -953,7 +1682,10 @@ impl VcInner {
 
         let mut count = 0;
 
-        while !queue.is_empty() && data_transfer_state.send_window.is_open() {
+        while !queue.is_empty()
+            && data_transfer_state.send_window.is_open()
+            && !data_transfer_state.is_remote_busy
+        {
             let SendData {
                 user_data,
                 qualifier,
@@ -962,7 +1694,7 @@ impl VcInner {
 
             let data = X25Data {
                 modulo: self.params.read().unwrap().modulo,
-                channel: self.channel,
+                channel: self.channel(),
                 send_seq: data_transfer_state.send_window.seq(),
                 recv_seq: data_transfer_state.recv_seq,
                 qualifier: *qualifier,
@@ -986,6 +1718,26 @@ impl VcInner {
             self.send_data_queue.1.notify_all();
         }
 
+        // Any data remaining in the queue is blocked by flow control.
+        if let VcState::DataTransfer(ref mut data_transfer_state) = *state {
+            let is_blocked = !queue.is_empty();
+
+            if is_blocked != data_transfer_state.is_blocked {
+                data_transfer_state.is_blocked = is_blocked;
+
+                self.stats
+                    .lock()
+                    .unwrap()
+                    .set_blocked(is_blocked, self.clock.now());
+
+                self.emit(if is_blocked {
+                    VcEvent::FlowBlocked
+                } else {
+                    VcEvent::FlowUnblocked
+                });
+            }
+        }
+
         (count, queue.len())
     }
 
@@ -997,7 +1749,7 @@ impl VcInner {
 
         let receive_ready = X25ReceiveReady {
             modulo: self.params.read().unwrap().modulo,
-            channel: self.channel,
+            channel: self.channel(),
             recv_seq,
         };
 
@@ -1006,26 +1758,108 @@ impl VcInner {
         }
     }
 
-    fn queue_recv_data(&self, data: X25Data) {
-        let params = self.params.read().unwrap();
+    fn receive_not_ready(&self, state: &mut VcState) {
+        let recv_seq = match *state {
+            VcState::DataTransfer(ref data_transfer_state) => data_transfer_state.recv_seq,
+            _ => panic!("unexpected state"),
+        };
 
-        if data.user_data.len() > params.recv_packet_size {
-            // TODO: "Packet too long"
+        let receive_not_ready = X25ReceiveNotReady {
+            modulo: self.params.read().unwrap().modulo,
+            channel: self.channel(),
+            recv_seq,
+        };
+
+        if let Err(err) = self.send_packet(&receive_not_ready.into()) {
+            self.out_of_order(state, err);
         }
+    }
 
-        let mut queue = self.recv_data_queue.0.lock().unwrap();
+    fn probe_if_idle(
+        &self,
+        state: &mut VcState,
+        idle_probe: Duration,
+        timeout: &mut Option<Duration>,
+    ) {
+        let VcState::DataTransfer(ref mut data_transfer_state) = *state else {
+            return;
+        };
 
-        if let Some(prev_data) = queue.back() {
-            if prev_data.more && data.qualifier != prev_data.qualifier {
-                // TODO: "Inconsistent Q-bit setting"
-            }
+        let elapsed = self.clock.elapsed(data_transfer_state.idle_since);
+
+        if elapsed < idle_probe {
+            *timeout = Some(idle_probe - elapsed);
+            return;
         }
 
+        // Sending a packet on an idle link ensures that a dead peer is detected
+        // by the underlying transport.
+        debug!(parent: self.span(), "link idle, sending probe");
+
+        data_transfer_state.idle_since = self.clock.now();
+
+        *timeout = Some(idle_probe);
+
+        self.receive_ready(state);
+    }
+
+    fn queue_recv_data(&self, data: X25Data) {
+        let mut queue = self.recv_data_queue.0.lock().unwrap();
+
         queue.push_back(data);
         self.recv_data_queue.1.notify_all();
     }
 
+    /// Discards the received packets of an incomplete packet sequence, that
+    /// will never be completed.
+    fn discard_incomplete_recv_data(&self) {
+        let mut queue = self.recv_data_queue.0.lock().unwrap();
+
+        while queue.back().is_some_and(|d| d.more) {
+            queue.pop_back();
+        }
+    }
+
+    /// Wakes the engine, so that a state change made outside of the engine is
+    /// observed and any timers are started.
+    ///
+    /// The state lock must not be held, as the engine queue lock is acquired to
+    /// ensure the wakeup is not lost while the engine is handling a packet.
+    fn wake_engine(&self) {
+        let _queue = self.engine_queue.lock().unwrap();
+
+        self.engine_wait.notify_all();
+    }
+
+    fn emit(&self, event: VcEvent) {
+        debug!(parent: self.span(), ?event, "event");
+
+        // Subscribers that have gone away are removed.
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
     fn change_state(&self, state: &mut VcState, new_state: VcState) {
+        // Leaving the data transfer state, by reset or clear, ends any period
+        // of being blocked.
+        if let VcState::DataTransfer(ref data_transfer_state) = *state {
+            if data_transfer_state.is_blocked {
+                self.stats
+                    .lock()
+                    .unwrap()
+                    .set_blocked(false, self.clock.now());
+            }
+        }
+
+        info!(
+            parent: self.span(),
+            from = state.name(),
+            to = new_state.name(),
+            "state changed"
+        );
+
         *state = new_state;
         self.state.1.notify_all();
     }
@@ -1035,7 +1869,17 @@ impl VcInner {
 
         packet.encode(&mut buf).map_err(io::Error::other)?;
 
-        self.send_link.lock().unwrap().send(&buf)
+        debug!(parent: self.span(), ?packet, "packet sent");
+
+        self.send_link.lock().unwrap().send(&buf)?;
+
+        self.stats
+            .lock()
+            .unwrap()
+            .stats
+            .record_sent(packet, buf.len());
+
+        Ok(())
     }
 }
 
@@ -1059,8 +1903,8 @@ fn create_call_request(
     X25CallRequest {
         modulo: params.modulo,
         channel,
-        called_addr: addr.clone(),
-        calling_addr: params.addr.clone(),
+        called_addr: format_addr(addr, params.addr_format),
+        calling_addr: format_addr(&params.addr, params.addr_format),
         facilities,
         call_user_data: Bytes::copy_from_slice(call_user_data),
     }
@@ -1088,6 +1932,18 @@ fn create_call_accept(channel: u16, params: &X25Params) -> X25CallAccept {
     }
 }
 
+/// Formats an address for the address format provided, an address without a
+/// TOA/NPI is assumed to be an X.121 address.
+fn format_addr(addr: &X121Addr, addr_format: X25AddrFormat) -> X121Addr {
+    match addr_format {
+        X25AddrFormat::NonToaNpi => addr.clone().without_type(),
+        X25AddrFormat::ToaNpi if addr.addr_type().is_none() => addr
+            .clone()
+            .with_type(X121AddrType::Unknown, X121NumberingPlan::X121),
+        X25AddrFormat::ToaNpi => addr.clone(),
+    }
+}
+
 fn negotiate_calling_params(call_accept: &X25CallAccept, params: &X25Params) -> X25Params {
     let mut params = params.clone();
 
@@ -1115,6 +1971,17 @@ fn negotiate_called_params(call_request: &X25CallRequest, params: &X25Params) ->
 
     params.modulo = call_request.modulo;
 
+    // The address format of the call request, indicated by the addresses having
+    // a TOA/NPI, is used for the remainder of the call.
+    let has_type = |a: &X121Addr| a.addr_type().is_some();
+
+    params.addr_format =
+        if has_type(&call_request.called_addr) || has_type(&call_request.calling_addr) {
+            X25AddrFormat::ToaNpi
+        } else {
+            X25AddrFormat::NonToaNpi
+        };
+
     // When negotiating facilties from a received call request, we are the "called"
     // party.
     let facilities = &call_request.facilities;
@@ -1132,6 +1999,15 @@ fn negotiate_called_params(call_request: &X25CallRequest, params: &X25Params) ->
     params
 }
 
+/// Returns `true` if more data can be received, that is, the received data is
+/// being consumed.
+///
+/// An incomplete packet sequence can't be consumed, so more data is always
+/// accepted to complete it.
+fn is_local_ready(queue: &VecDeque<X25Data>) -> bool {
+    queue.len() < RECV_QUEUE_LIMIT || queue.iter().all(|d| d.more)
+}
+
 fn get_packet_size(facilities: &[X25Facility]) -> Option<(usize, usize)> {
     facilities.iter().find_map(|f| match f {
         X25Facility::PacketSize {
@@ -1156,6 +2032,43 @@ fn clamp_window_size(size: u8, modulo: X25Modulo) -> u8 {
     min(size, (modulo as u8) - 1)
 }
 
+impl SvcStats {
+    fn record_sent(&mut self, packet: &X25Packet, len: usize) {
+        self.packets_sent += 1;
+        self.bytes_sent += len as u64;
+
+        match packet {
+            X25Packet::Data(_) => self.data_packets_sent += 1,
+            X25Packet::ReceiveReady(_) => self.rr_sent += 1,
+            X25Packet::ReceiveNotReady(_) => self.rnr_sent += 1,
+            _ => {}
+        }
+    }
+
+    fn record_received(&mut self, packet: &X25Packet, len: usize) {
+        self.packets_received += 1;
+        self.bytes_received += len as u64;
+
+        match packet {
+            X25Packet::Data(_) => self.data_packets_received += 1,
+            X25Packet::ReceiveReady(_) => self.rr_received += 1,
+            X25Packet::ReceiveNotReady(_) => self.rnr_received += 1,
+            X25Packet::ResetRequest(_) => self.resets_received += 1,
+            _ => {}
+        }
+    }
+}
+
+impl StatsState {
+    fn set_blocked(&mut self, is_blocked: bool, now: Instant) {
+        if is_blocked {
+            self.blocked_since.get_or_insert(now);
+        } else if let Some(blocked_since) = self.blocked_since.take() {
+            self.stats.blocked_time += now.saturating_duration_since(blocked_since);
+        }
+    }
+}
+
 impl DataTransferState {
     #[must_use]
     fn update_recv_seq(&mut self, seq: u8) -> bool {
@@ -1172,6 +2085,33 @@ impl DataTransferState {
     fn update_send_window(&mut self, seq: u8) -> bool {
         self.send_window.update_start(seq)
     }
+
+    /// Updates the incomplete packet sequence being received, returning the
+    /// diagnostic code if the packet is not valid.
+    fn update_recv_sequence(&mut self, data: &X25Data, params: &X25Params) -> Result<(), u8> {
+        if data.user_data.len() > params.recv_packet_size {
+            return Err(39); // Packet too long
+        }
+
+        let (qualifier, len) = self.recv_sequence.unwrap_or((data.qualifier, 0));
+
+        if data.qualifier != qualifier {
+            return Err(83); // Inconsistent Q-bit setting
+        }
+
+        let len = len + data.user_data.len();
+
+        if params
+            .max_recv_sequence_len
+            .is_some_and(|max_len| len > max_len)
+        {
+            return Err(39); // Packet too long
+        }
+
+        self.recv_sequence = data.more.then_some((qualifier, len));
+
+        Ok(())
+    }
 }
 
 fn pop_complete_data(queue: &mut VecDeque<X25Data>) -> Option<(Bytes, bool)> {
@@ -1197,11 +2137,821 @@ fn pop_complete_data(queue: &mut VecDeque<X25Data>) -> Option<(Bytes, bool)> {
 }
 
 fn split_xot_link(link: XotLink) -> (XotLink, XotLink) {
-    // crazy hack...
-    let tcp_stream = link.into_stream();
+    // The original link is used to receive, so that any data already buffered
+    // is not lost.
+    let send_link = link.try_clone().unwrap();
+
+    (send_link, link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use crate::clock::{ManualClock, SystemClock};
+    use crate::test_util::{peer_pair, ScriptedPeer, X25Script};
+    use crate::x25::packet::{X25Interrupt, X25PacketType};
+
+    fn params(role: X25Role) -> X25Params {
+        X25Params {
+            addr: X121Addr::from_str("1111").unwrap(),
+            modulo: X25Modulo::Normal,
+            send_packet_size: 128,
+            send_window_size: 2,
+            recv_packet_size: 128,
+            recv_window_size: 2,
+            max_recv_sequence_len: None,
+            t21: Duration::from_secs(5),
+            t22: Duration::from_secs(5),
+            t23: Duration::from_secs(5),
+            addr_format: X25AddrFormat::NonToaNpi,
+            idle_probe: None,
+            role,
+            channels: 1..=1024,
+        }
+    }
+
+    #[test]
+    fn call_collision_as_dce() {
+        let (link, mut peer) = peer_pair().unwrap();
+
+        let call = thread::spawn(move || {
+            let addr = X121Addr::from_str("2222").unwrap();
+
+            Svc::call(
+                link,
+                1,
+                &addr,
+                b"",
+                &params(X25Role::Dce),
+                Arc::new(SystemClock),
+            )
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallRequest(_)));
+
+        // The peer calls on the same channel.
+        let addr = X121Addr::from_str("1111").unwrap();
+        let call_request = create_call_request(1, &addr, b"", &params(X25Role::Dte));
+
+        peer.send(call_request).unwrap();
+
+        let err = call.join().unwrap().err().unwrap();
+
+        let incoming_call = SvcCallCollision::from_error(err)
+            .unwrap()
+            .into_incoming_call();
+
+        assert_eq!(incoming_call.request().called_addr, addr);
+
+        let svc = incoming_call.accept().unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallAccept(_)));
+
+        assert!(svc.is_connected());
+    }
+
+    #[test]
+    fn call_collision_as_dte() {
+        let (link, mut peer) = peer_pair().unwrap();
+
+        let call = thread::spawn(move || {
+            let addr = X121Addr::from_str("2222").unwrap();
+
+            Svc::call(
+                link,
+                1,
+                &addr,
+                b"",
+                &params(X25Role::Dte),
+                Arc::new(SystemClock),
+            )
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallRequest(_)));
+
+        // The peer calls on the same channel, and then cancels that call in favour
+        // of ours.
+        let addr = X121Addr::from_str("1111").unwrap();
+        let call_request = create_call_request(1, &addr, b"", &params(X25Role::Dce));
+
+        peer.send(call_request).unwrap();
+
+        let call_accept = create_call_accept(1, &params(X25Role::Dce));
+
+        peer.send(call_accept).unwrap();
+
+        let svc = call.join().unwrap().unwrap();
+
+        assert!(svc.is_connected());
+    }
+
+    fn connect(params: X25Params) -> (Svc, ScriptedPeer) {
+        connect_with_clock(params, Arc::new(SystemClock))
+    }
+
+    fn connect_with_clock(params: X25Params, clock: Arc<dyn Clock>) -> (Svc, ScriptedPeer) {
+        let (link, mut peer) = peer_pair().unwrap();
+
+        let call = thread::spawn({
+            let params = params.clone();
+
+            move || {
+                let addr = X121Addr::from_str("2222").unwrap();
+
+                Svc::call(link, 1, &addr, b"", &params, clock)
+            }
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallRequest(_)));
+
+        peer.send(create_call_accept(1, &params)).unwrap();
+
+        (call.join().unwrap().unwrap(), peer)
+    }
+
+    fn recv_event(events: &Receiver<VcEvent>) -> VcEvent {
+        events.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
 
-    (
-        XotLink::new(tcp_stream.try_clone().unwrap()),
-        XotLink::new(tcp_stream),
-    )
+    #[test]
+    fn interrupt() {
+        let (svc, mut peer) = connect(params(X25Role::Dte));
+
+        let events = svc.subscribe();
+
+        svc.interrupt(b"\x01").unwrap();
+
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::Interrupt(interrupt) if interrupt.user_data[..] == b"\x01"[..]
+        ));
+
+        // Only one interrupt may be outstanding...
+        let err = svc.interrupt(b"\x02").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+        let interrupt_confirm = X25InterruptConfirm {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+        };
+
+        peer.send(interrupt_confirm).unwrap();
+
+        // An interrupt from the peer ensures the confirmation has been processed.
+        let interrupt = X25Interrupt {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            user_data: Bytes::from_static(b"\x03"),
+        };
+
+        peer.send(interrupt).unwrap();
+
+        assert!(matches!(recv_event(&events), VcEvent::Interrupt(_)));
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::InterruptConfirm(_)
+        ));
+
+        assert!(svc.interrupt(b"\x02").is_ok());
+    }
+
+    #[test]
+    fn interrupt_invalid() {
+        let (svc, _peer) = connect(params(X25Role::Dte));
+
+        let err = svc.interrupt(b"").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let err = svc.interrupt(&[0; 33]).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn subscribe() {
+        let (svc, mut peer) = connect(params(X25Role::Dte));
+
+        let events = svc.subscribe();
+
+        // Remote busy, followed by an interrupt which ensures the RNR has been
+        // processed once the interrupt is confirmed...
+        let receive_not_ready = X25ReceiveNotReady {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            recv_seq: 0,
+        };
+
+        peer.send(receive_not_ready).unwrap();
+
+        let interrupt = X25Interrupt {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            user_data: Bytes::from_static(b"\x01"),
+        };
+
+        peer.send(interrupt).unwrap();
+
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::InterruptConfirm(_)
+        ));
+
+        assert!(matches!(
+            recv_event(&events),
+            VcEvent::Interrupt(user_data) if user_data[..] == b"\x01"[..]
+        ));
+
+        svc.send(Bytes::from_static(b"hello"), false).unwrap();
+
+        assert!(matches!(recv_event(&events), VcEvent::FlowBlocked));
+
+        let receive_ready = X25ReceiveReady {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            recv_seq: 0,
+        };
+
+        peer.send(receive_ready).unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::Data(_)));
+        assert!(matches!(recv_event(&events), VcEvent::FlowUnblocked));
+
+        // Reset...
+        let reset_request = X25ResetRequest {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            cause_code: 1,
+            diagnostic_code: 2,
+        };
+
+        peer.send(reset_request).unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ResetConfirm(_)));
+
+        assert!(matches!(
+            recv_event(&events),
+            VcEvent::Reset {
+                cause_code: 1,
+                diagnostic_code: 2
+            }
+        ));
+
+        // Clear...
+        let clear_request = X25ClearRequest {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            cause_code: 3,
+            diagnostic_code: 4,
+            called_addr: X121Addr::null(),
+            calling_addr: X121Addr::null(),
+            facilities: Vec::new(),
+            clear_user_data: Bytes::new(),
+        };
+
+        peer.send(clear_request).unwrap();
+
+        assert!(matches!(
+            recv_event(&events),
+            VcEvent::Cleared {
+                cause_code: 3,
+                diagnostic_code: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn stats() {
+        let (svc, mut peer) = connect(params(X25Role::Dte));
+
+        svc.send(Bytes::from_static(b"hello"), false).unwrap();
+        svc.flush().unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::Data(_)));
+
+        let data = X25Data {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            send_seq: 0,
+            recv_seq: 1,
+            qualifier: false,
+            delivery: false,
+            more: false,
+            user_data: Bytes::from_static(b"world"),
+        };
+
+        peer.send(data).unwrap();
+
+        assert_eq!(
+            svc.recv().unwrap(),
+            Some((Bytes::from_static(b"world"), false))
+        );
+
+        let stats = svc.stats();
+
+        assert_eq!(stats.data_packets_sent, 1);
+        assert_eq!(stats.data_packets_received, 1);
+        assert_eq!(stats.packets_received, 2); // Call accept, data
+        assert_eq!(stats.resets_sent, 0);
+        assert!(stats.call_setup_time.is_some());
+
+        let link_stats = svc.link_stats();
+
+        assert!(link_stats.packets_sent >= 2); // Call request, data
+        assert_eq!(link_stats.packets_received, 2);
+        assert_eq!(link_stats.bytes_received, stats.bytes_received + 2 * 4);
+    }
+
+    #[test]
+    fn call_with_events_connected() {
+        let params = params(X25Role::Dte);
+        let (link, mut peer) = peer_pair().unwrap();
+        let (tx, events) = channel();
+
+        let call = thread::spawn({
+            let params = params.clone();
+
+            move || {
+                let addr = X121Addr::from_str("2222").unwrap();
+
+                Svc::call_with_events(link, 1, &addr, b"", &params, Arc::new(SystemClock), tx)
+            }
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallRequest(_)));
+
+        peer.send(create_call_accept(1, &params)).unwrap();
+
+        let svc = call.join().unwrap().unwrap();
+
+        assert!(matches!(recv_event(&events), VcEvent::Connected));
+
+        assert!(svc.is_connected());
+    }
+
+    #[test]
+    fn call_with_events_t21_timeout() {
+        let params = params(X25Role::Dte);
+        let clock = Arc::new(ManualClock::new());
+        let (link, peer) = peer_pair().unwrap();
+        let (tx, events) = channel();
+
+        let script = X25Script::new()
+            .expect_type(X25PacketType::CallRequest)
+            .advance(&clock, params.t21 + Duration::from_secs(1))
+            .expect_type(X25PacketType::ClearRequest);
+
+        let peer = peer.spawn(script);
+
+        let addr = X121Addr::from_str("2222").unwrap();
+
+        let err = Svc::call_with_events(link, 1, &addr, b"", &params, clock, tx)
+            .err()
+            .unwrap();
+
+        assert_eq!(X25Timer::from_error(&err), Some(X25Timer::T21));
+
+        assert!(matches!(
+            recv_event(&events),
+            VcEvent::TimerExpired(X25Timer::T21)
+        ));
+
+        peer.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn local_busy() {
+        let (svc, mut peer) = connect(params(X25Role::Dte));
+
+        for send_seq in 0..RECV_QUEUE_LIMIT {
+            peer.send(data((send_seq % 8) as u8, false, false, b"hello"))
+                .unwrap();
+        }
+
+        for _ in 1..RECV_QUEUE_LIMIT {
+            assert!(matches!(peer.recv().unwrap(), X25Packet::ReceiveReady(_)));
+        }
+
+        // The remote party is told to stop sending, until data is consumed.
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::ReceiveNotReady(rnr) if usize::from(rnr.recv_seq) == RECV_QUEUE_LIMIT % 8
+        ));
+
+        assert_eq!(
+            svc.recv().unwrap(),
+            Some((Bytes::from_static(b"hello"), false))
+        );
+
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::ReceiveReady(rr) if usize::from(rr.recv_seq) == RECV_QUEUE_LIMIT % 8
+        ));
+
+        assert_eq!(svc.stats().rnr_sent, 1);
+    }
+
+    #[test]
+    fn t21_timeout() {
+        let params = params(X25Role::Dte);
+        let clock = Arc::new(ManualClock::new());
+        let (link, peer) = peer_pair().unwrap();
+
+        let script = X25Script::new()
+            .expect_type(X25PacketType::CallRequest)
+            .advance(&clock, params.t21 + Duration::from_secs(1))
+            .expect("clear request C:19 D:49", |p| {
+                matches!(
+                    p,
+                    X25Packet::ClearRequest(X25ClearRequest {
+                        cause_code: 19,
+                        diagnostic_code: 49,
+                        ..
+                    })
+                )
+            });
+
+        let peer = peer.spawn(script);
+
+        let addr = X121Addr::from_str("2222").unwrap();
+
+        let err = Svc::call(link, 1, &addr, b"", &params, clock)
+            .err()
+            .unwrap();
+
+        assert_eq!(X25Timer::from_error(&err), Some(X25Timer::T21));
+
+        peer.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn recv_complete_data_sequence() {
+        let params = params(X25Role::Dte);
+        let (link, peer) = peer_pair().unwrap();
+
+        let script = X25Script::new()
+            .expect("call request with packet size facility", |p| {
+                matches!(
+                    p,
+                    X25Packet::CallRequest(call_request)
+                        if get_packet_size(&call_request.facilities) == Some((128, 128))
+                )
+            })
+            .send(create_call_accept(1, &params))
+            .send(data(0, false, true, b"hello "))
+            .expect(
+                "RR P(R)=1",
+                |p| matches!(p, X25Packet::ReceiveReady(rr) if rr.recv_seq == 1),
+            )
+            .send(data(1, false, false, b"world"))
+            .expect(
+                "RR P(R)=2",
+                |p| matches!(p, X25Packet::ReceiveReady(rr) if rr.recv_seq == 2),
+            );
+
+        let peer = peer.spawn(script);
+
+        let addr = X121Addr::from_str("2222").unwrap();
+
+        let svc = Svc::call(link, 1, &addr, b"", &params, Arc::new(SystemClock)).unwrap();
+
+        assert_eq!(
+            svc.recv().unwrap(),
+            Some((Bytes::from_static(b"hello world"), false))
+        );
+
+        peer.join().unwrap().unwrap();
+    }
+
+    fn data(send_seq: u8, qualifier: bool, more: bool, user_data: &'static [u8]) -> X25Data {
+        X25Data {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+            send_seq,
+            recv_seq: 0,
+            qualifier,
+            delivery: false,
+            more,
+            user_data: Bytes::from_static(user_data),
+        }
+    }
+
+    fn assert_reset_request(packet: X25Packet, diagnostic_code: u8) {
+        assert!(matches!(
+            packet,
+            X25Packet::ResetRequest(reset_request)
+                if reset_request.cause_code == 5 && reset_request.diagnostic_code == diagnostic_code
+        ));
+    }
+
+    #[test]
+    fn recv_sequence_too_long() {
+        let params = X25Params {
+            max_recv_sequence_len: Some(8),
+            ..params(X25Role::Dte)
+        };
+
+        let (svc, mut peer) = connect(params);
+
+        peer.send(data(0, false, true, b"hello ")).unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ReceiveReady(_)));
+
+        peer.send(data(1, false, false, b"world")).unwrap();
+
+        assert_reset_request(peer.recv().unwrap(), 39);
+
+        peer.send(X25ResetConfirm {
+            modulo: X25Modulo::Normal,
+            channel: 1,
+        })
+        .unwrap();
+
+        // The incomplete sequence is discarded.
+        peer.send(data(0, false, false, b"world")).unwrap();
+
+        assert_eq!(
+            svc.recv().unwrap(),
+            Some((Bytes::from_static(b"world"), false))
+        );
+    }
+
+    #[test]
+    fn recv_sequence_inconsistent_qualifier() {
+        let (_svc, mut peer) = connect(params(X25Role::Dte));
+
+        peer.send(data(0, true, true, b"hello ")).unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ReceiveReady(_)));
+
+        peer.send(data(1, false, false, b"world")).unwrap();
+
+        assert_reset_request(peer.recv().unwrap(), 83);
+    }
+
+    #[test]
+    fn recv_packet() {
+        let (svc, mut peer) = connect(params(X25Role::Dte));
+
+        peer.send(data(0, false, true, b"hello ")).unwrap();
+        peer.send(data(1, false, false, b"world")).unwrap();
+
+        assert_eq!(
+            svc.recv_packet().unwrap(),
+            Some((Bytes::from_static(b"hello "), false, true))
+        );
+
+        assert_eq!(
+            svc.recv_packet().unwrap(),
+            Some((Bytes::from_static(b"world"), false, false))
+        );
+    }
+
+    fn listen(params: &X25Params) -> (SvcIncomingCall, ScriptedPeer) {
+        listen_with(params, params)
+    }
+
+    fn listen_with(
+        params: &X25Params,
+        calling_params: &X25Params,
+    ) -> (SvcIncomingCall, ScriptedPeer) {
+        let (link, mut peer) = peer_pair().unwrap();
+
+        let addr = X121Addr::from_str("1111").unwrap();
+
+        peer.send(create_call_request(1, &addr, b"", calling_params))
+            .unwrap();
+
+        let clock = Arc::new(SystemClock);
+
+        let incoming_call =
+            Svc::listen_timeout(link, 1, params, clock, Duration::from_secs(5)).unwrap();
+
+        (incoming_call, peer)
+    }
+
+    #[test]
+    fn listen_timeout_expired() {
+        let (link, mut peer) = peer_pair().unwrap();
+        let clock = Arc::new(ManualClock::new());
+
+        let listen = thread::spawn({
+            let clock = Arc::clone(&clock);
+
+            move || {
+                let params = params(X25Role::Dte);
+
+                Svc::listen_timeout(link, 1, &params, clock, Duration::from_secs(30))
+            }
+        });
+
+        // The listener may not have started waiting yet, in which case it would
+        // not observe the clock being advanced.
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while !listen.is_finished() {
+            assert!(Instant::now() < deadline, "listen not timed out");
+
+            clock.advance(Duration::from_secs(31));
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let err = listen.join().unwrap().err().unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+        // The link is closed.
+        assert!(peer.recv().is_err());
+    }
+
+    #[test]
+    fn accept_connected() {
+        let params = params(X25Role::Dte);
+        let (incoming_call, mut peer) = listen(&params);
+
+        let events = incoming_call.subscribe();
+
+        let svc = incoming_call.accept().unwrap();
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::CallAccept(_)));
+
+        assert!(matches!(recv_event(&events), VcEvent::Connected));
+
+        assert!(svc.is_connected());
+    }
+
+    #[test]
+    fn accept_with() {
+        let params = params(X25Role::Dte);
+        let (incoming_call, mut peer) = listen(&params);
+
+        let addr = X121Addr::from_str("11112").unwrap();
+
+        let facilities = [
+            X25Facility::ClassA(0x08, (0x01,)), // Called line address modified
+            X25Facility::ClassA(0x02, (0xaa,)), // Throughput class
+        ];
+
+        let svc = incoming_call
+            .accept_with(&addr, b"hello", &facilities)
+            .unwrap();
+
+        let X25Packet::CallAccept(call_accept) = peer.recv().unwrap() else {
+            panic!("expected call accept");
+        };
+
+        assert_eq!(call_accept.called_addr, addr);
+        assert_eq!(call_accept.called_user_data, Bytes::from_static(b"hello"));
+        assert_eq!(get_packet_size(&call_accept.facilities), Some((128, 128)));
+        assert!(call_accept.facilities.ends_with(&facilities));
+
+        assert!(svc.is_connected());
+    }
+
+    #[test]
+    fn accept_with_toa_npi() {
+        let calling_params = X25Params {
+            addr_format: X25AddrFormat::ToaNpi,
+            ..params(X25Role::Dce)
+        };
+
+        let (incoming_call, mut peer) = listen_with(&params(X25Role::Dte), &calling_params);
+
+        let addr = X121Addr::from_str("2222").unwrap();
+
+        incoming_call.accept_with(&addr, b"", &[]).unwrap();
+
+        let X25Packet::CallAccept(call_accept) = peer.recv().unwrap() else {
+            panic!("expected call accept");
+        };
+
+        // The address format of the call request is used.
+        assert_eq!(
+            call_accept.called_addr.addr_type(),
+            Some((X121AddrType::Unknown, X121NumberingPlan::X121))
+        );
+    }
+
+    #[test]
+    fn accept_with_invalid() {
+        let params = params(X25Role::Dte);
+        let (incoming_call, mut peer) = listen(&params);
+
+        let facilities = [X25Facility::WindowSize {
+            from_called: 7,
+            from_calling: 7,
+        }];
+
+        let err = incoming_call
+            .accept_with(&X121Addr::null(), b"", &facilities)
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // The call is cleared, rather than left waiting for an accept.
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ClearRequest(_)));
+    }
+
+    #[test]
+    fn accept_with_invalid_user_data() {
+        let params = params(X25Role::Dte);
+        let (incoming_call, mut peer) = listen(&params);
+
+        let err = incoming_call
+            .accept_with(&X121Addr::null(), &[0; 256], &[])
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ClearRequest(_)));
+    }
+
+    #[test]
+    fn t22_retransmit_and_clear() {
+        let params = params(X25Role::Dte);
+        let clock = Arc::new(ManualClock::new());
+        let (svc, mut peer) = connect_with_clock(params.clone(), Arc::clone(&clock) as _);
+
+        let reset = thread::spawn({
+            let svc = svc.clone();
+
+            move || svc.reset(0, 0)
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ResetRequest(_)));
+
+        clock.advance(params.t22 + Duration::from_secs(1));
+
+        // The reset request is retransmitted once...
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ResetRequest(_)));
+
+        clock.advance(params.t22 + Duration::from_secs(1));
+
+        // ...and then the call is cleared.
+        assert!(matches!(
+            peer.recv().unwrap(),
+            X25Packet::ClearRequest(X25ClearRequest {
+                cause_code: 19,
+                diagnostic_code: 51,
+                ..
+            })
+        ));
+
+        let err = reset.join().unwrap().unwrap_err();
+
+        assert_eq!(X25Timer::from_error(&err), Some(X25Timer::T22));
+        assert_eq!(svc.stats().packets_retransmitted, 1);
+    }
+
+    #[test]
+    fn t23_retransmit_and_out_of_order() {
+        let params = params(X25Role::Dte);
+        let clock = Arc::new(ManualClock::new());
+        let (svc, mut peer) = connect_with_clock(params.clone(), Arc::clone(&clock) as _);
+
+        let clear = thread::spawn({
+            let svc = svc.clone();
+
+            move || svc.clear(0, 0)
+        });
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ClearRequest(_)));
+
+        clock.advance(params.t23 + Duration::from_secs(1));
+
+        // The clear request is retransmitted once...
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ClearRequest(_)));
+
+        clock.advance(params.t23 + Duration::from_secs(1));
+
+        // ...and then the channel is out of order.
+        let err = clear.join().unwrap().unwrap_err();
+
+        assert_eq!(X25Timer::from_error(&err), Some(X25Timer::T23));
+
+        // The link is closed.
+        assert!(peer.recv().is_err());
+    }
+
+    #[test]
+    fn idle_probe() {
+        let clock = Arc::new(ManualClock::new());
+
+        let params = X25Params {
+            idle_probe: Some(Duration::from_secs(30)),
+            ..params(X25Role::Dte)
+        };
+
+        let (_svc, mut peer) = connect_with_clock(params, Arc::clone(&clock) as _);
+
+        clock.advance(Duration::from_secs(31));
+
+        assert!(matches!(peer.recv().unwrap(), X25Packet::ReceiveReady(_)));
+    }
 }
@@ -60,6 +60,7 @@ pub enum X29PadMessage {
     SetRead(Vec<(u8, u8)>),
     Indicate(Vec<(u8, Result<u8, X3ParamError>)>),
     ClearInvitation,
+    IndicationOfBreak(Vec<(u8, u8)>),
 }
 
 impl X29PadMessage {
@@ -95,6 +96,13 @@ impl X29PadMessage {
 
                 1 + len
             }
+            X29PadMessage::IndicationOfBreak(params) => {
+                buf.put_u8(0x03);
+
+                let len = encode_params_request(params, buf);
+
+                1 + len
+            }
             X29PadMessage::ClearInvitation => {
                 buf.put_u8(0x01);
 
@@ -146,6 +154,11 @@ impl X29PadMessage {
 
                 Ok(X29PadMessage::ClearInvitation)
             }
+            0x03 => {
+                let params = decode_params_request(buf)?;
+
+                Ok(X29PadMessage::IndicationOfBreak(params))
+            }
             _ => Err(format!("unrecognized X.29 PAD message: {code}")),
         }
     }
@@ -384,6 +397,34 @@ mod tests {
             Ok(X29PadMessage::ClearInvitation)
         );
     }
+
+    #[test]
+    fn encode_indication_of_break_message() {
+        let message = X29PadMessage::IndicationOfBreak(vec![(8, 1)]);
+
+        let mut buf = BytesMut::new();
+
+        assert_eq!(message.encode(&mut buf), 3);
+
+        assert_eq!(&buf[..], b"\x03\x08\x01");
+    }
+
+    #[test]
+    fn decode_indication_of_break_message() {
+        let buf = Bytes::from_static(b"\x03");
+
+        assert_eq!(
+            X29PadMessage::decode(buf),
+            Ok(X29PadMessage::IndicationOfBreak(vec![]))
+        );
+
+        let buf = Bytes::from_static(b"\x03\x08\x01");
+
+        assert_eq!(
+            X29PadMessage::decode(buf),
+            Ok(X29PadMessage::IndicationOfBreak(vec![(8, 1)]))
+        );
+    }
 }
 
 #[cfg(fuzzing)]
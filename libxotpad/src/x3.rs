@@ -20,6 +20,38 @@ pub enum X3ParamError {
     Other,
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct X3Recall(u8);
+
+impl Deref for X3Recall {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3Recall {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 | 1 | 32..=126 => Ok(X3Recall(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl X3Recall {
+    pub fn is_match(&self, byte: u8) -> bool {
+        match self.0 {
+            0 => false,
+            1 => byte == /* DLE */ 0x10,
+            recall => byte == recall,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct X3Echo(u8);
 
@@ -149,6 +181,246 @@ impl From<X3Idle> for Option<Duration> {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct X3AncillaryControl(u8);
+
+impl Deref for X3AncillaryControl {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3AncillaryControl {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            // The PAD never needs to stop the DTE in the command state, so flow
+            // control in that state (2) is not supported.
+            0 | 1 => Ok(X3AncillaryControl(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl X3AncillaryControl {
+    /// Returns `true` if the PAD should use X-ON and X-OFF to control the flow
+    /// of data from the DTE, while in the data transfer state.
+    pub fn in_data_transfer(&self) -> bool {
+        self.0 == 1
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3ServiceSignals(u8);
+
+impl Deref for X3ServiceSignals {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3ServiceSignals {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 | 1 | 4 | 5 | 8..=15 => Ok(X3ServiceSignals(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl X3ServiceSignals {
+    /// Returns `true` if service signals, other than the prompt, are sent to the
+    /// DTE.
+    pub fn has_signals(&self) -> bool {
+        self.0 & 1 == 1 || self.0 >= 8
+    }
+
+    /// Returns `true` if the prompt service signal is sent to the DTE.
+    pub fn has_prompt(&self) -> bool {
+        self.0 & 4 == 4 || self.0 >= 8
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3Break(u8);
+
+impl Deref for X3Break {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3Break {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0..=31 => Ok(X3Break(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl X3Break {
+    pub fn interrupt(&self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    pub fn reset(&self) -> bool {
+        self.0 & 2 == 2
+    }
+
+    pub fn indicate(&self) -> bool {
+        self.0 & 4 == 4
+    }
+
+    pub fn escape(&self) -> bool {
+        self.0 & 8 == 8
+    }
+
+    pub fn discard_output(&self) -> bool {
+        self.0 & 16 == 16
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3DiscardOutput(u8);
+
+impl Deref for X3DiscardOutput {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3DiscardOutput {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 | 1 => Ok(X3DiscardOutput(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl From<X3DiscardOutput> for bool {
+    fn from(discard: X3DiscardOutput) -> Self {
+        match discard {
+            X3DiscardOutput(0) => false,
+            X3DiscardOutput(1) => true,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3CrPadding(u8);
+
+impl Deref for X3CrPadding {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u8> for X3CrPadding {
+    fn from(value: u8) -> Self {
+        X3CrPadding(value)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3LineFolding(u8);
+
+impl Deref for X3LineFolding {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u8> for X3LineFolding {
+    fn from(value: u8) -> Self {
+        X3LineFolding(value)
+    }
+}
+
+impl From<X3LineFolding> for Option<usize> {
+    fn from(folding: X3LineFolding) -> Self {
+        match folding {
+            X3LineFolding(0) => None,
+            X3LineFolding(count) => Some(usize::from(count)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3Speed(u8);
+
+impl Deref for X3Speed {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3Speed {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0..=18 => Ok(X3Speed(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3FlowControl(u8);
+
+impl Deref for X3FlowControl {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3FlowControl {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 | 1 => Ok(X3FlowControl(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl From<X3FlowControl> for bool {
+    fn from(flow_control: X3FlowControl) -> Self {
+        match flow_control {
+            X3FlowControl(0) => false,
+            X3FlowControl(1) => true,
+            _ => unreachable!(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct X3LfInsert(u8);
 
@@ -186,6 +458,23 @@ impl X3LfInsert {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct X3LfPadding(u8);
+
+impl Deref for X3LfPadding {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u8> for X3LfPadding {
+    fn from(value: u8) -> Self {
+        X3LfPadding(value)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct X3Editing(u8);
 
@@ -217,3 +506,252 @@ impl From<X3Editing> for bool {
         }
     }
 }
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3EditingSignals(u8);
+
+impl Deref for X3EditingSignals {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3EditingSignals {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 | 1 | 2 | 8 | 32..=126 => Ok(X3EditingSignals(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3EchoMask(u8);
+
+impl Deref for X3EchoMask {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u8> for X3EchoMask {
+    fn from(value: u8) -> Self {
+        X3EchoMask(value)
+    }
+}
+
+impl X3EchoMask {
+    /// Returns `true` if the byte should not be echoed.
+    ///
+    /// The editing characters (64) are defined by the delegate parameters, so
+    /// they must be checked separately.
+    pub fn is_match(&self, byte: u8) -> bool {
+        let mask = self.0;
+
+        // CR (0x0d)
+        if mask & 1 == 1 && byte == 0x0d {
+            return true;
+        }
+
+        // LF (0x0a)
+        if mask & 2 == 2 && byte == 0x0a {
+            return true;
+        }
+
+        // VT (0x0b), HT (0x09), FF (0x0c)
+        if mask & 4 == 4 && [0x0b, 0x09, 0x0c].contains(&byte) {
+            return true;
+        }
+
+        // BEL (0x07), BS (0x08)
+        if mask & 8 == 8 && [0x07, 0x08].contains(&byte) {
+            return true;
+        }
+
+        // ESC (0x1b), ENQ (0x05)
+        if mask & 16 == 16 && [0x1b, 0x05].contains(&byte) {
+            return true;
+        }
+
+        // ACK (0x06), NAK (0x15), STX (0x02), SOH (0x01), EOT (0x04), ETB (0x17), ETX (0x03)
+        if mask & 32 == 32 && [0x06, 0x15, 0x02, 0x01, 0x04, 0x17, 0x03].contains(&byte) {
+            return true;
+        }
+
+        // DEL (0x7f), and everything else from IA5 columns 0 and 1...
+        if mask & 128 == 128
+            && [
+                0x7f, 0x00, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x16, 0x18, 0x19, 0x1a, 0x1c,
+                0x1d, 0x1e, 0x1f,
+            ]
+            .contains(&byte)
+        {
+            return true;
+        }
+
+        false
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3Parity(u8);
+
+impl Deref for X3Parity {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<u8> for X3Parity {
+    type Error = X3ParamError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0..=3 => Ok(X3Parity(value)),
+            _ => Err(X3ParamError::InvalidValue),
+        }
+    }
+}
+
+impl X3Parity {
+    /// Checks the parity of a byte from the DTE, returning the byte without the
+    /// parity bit or `None` if the parity is incorrect.
+    pub fn check(&self, byte: u8) -> Option<u8> {
+        if self.0 & 1 == 0 {
+            return Some(byte);
+        }
+
+        if byte.count_ones() % 2 != 0 {
+            return None;
+        }
+
+        Some(byte & 0x7f)
+    }
+
+    /// Generates the parity of a byte to the DTE.
+    pub fn generate(&self, byte: u8) -> u8 {
+        if self.0 & 2 == 0 {
+            return byte;
+        }
+
+        let byte = byte & 0x7f;
+
+        if byte.count_ones() % 2 != 0 {
+            return byte | 0x80;
+        }
+
+        byte
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct X3PageWait(u8);
+
+impl Deref for X3PageWait {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u8> for X3PageWait {
+    fn from(value: u8) -> Self {
+        X3PageWait(value)
+    }
+}
+
+impl From<X3PageWait> for Option<usize> {
+    fn from(page_wait: X3PageWait) -> Self {
+        match page_wait {
+            X3PageWait(0) => None,
+            X3PageWait(count) => Some(usize::from(count)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recall_is_match() {
+        assert!(!X3Recall::try_from(0).unwrap().is_match(/* DLE */ 0x10));
+        assert!(X3Recall::try_from(1).unwrap().is_match(/* DLE */ 0x10));
+        assert!(X3Recall::try_from(126).unwrap().is_match(b'~'));
+        assert!(!X3Recall::try_from(126).unwrap().is_match(/* DLE */ 0x10));
+    }
+
+    #[test]
+    fn recall_invalid() {
+        assert!(X3Recall::try_from(2).is_err());
+        assert!(X3Recall::try_from(127).is_err());
+    }
+
+    #[test]
+    fn ancillary_control_invalid() {
+        assert!(X3AncillaryControl::try_from(2).is_err());
+    }
+
+    #[test]
+    fn service_signals() {
+        let signals = X3ServiceSignals::try_from(1).unwrap();
+
+        assert!(signals.has_signals());
+        assert!(!signals.has_prompt());
+
+        let signals = X3ServiceSignals::try_from(4).unwrap();
+
+        assert!(!signals.has_signals());
+        assert!(signals.has_prompt());
+
+        let signals = X3ServiceSignals::try_from(0).unwrap();
+
+        assert!(!signals.has_signals());
+        assert!(!signals.has_prompt());
+    }
+
+    #[test]
+    fn parity_check() {
+        let parity = X3Parity::try_from(1).unwrap();
+
+        assert_eq!(parity.check(b'A'), Some(b'A'));
+        assert_eq!(parity.check(b'C' | 0x80), Some(b'C'));
+        assert_eq!(parity.check(b'C'), None);
+
+        let parity = X3Parity::try_from(0).unwrap();
+
+        assert_eq!(parity.check(b'C' | 0x80), Some(b'C' | 0x80));
+    }
+
+    #[test]
+    fn parity_generate() {
+        let parity = X3Parity::try_from(2).unwrap();
+
+        assert_eq!(parity.generate(b'A'), b'A');
+        assert_eq!(parity.generate(b'C'), b'C' | 0x80);
+    }
+
+    #[test]
+    fn echo_mask() {
+        let mask = X3EchoMask::from(1 | 2);
+
+        assert!(mask.is_match(/* CR */ 0x0d));
+        assert!(mask.is_match(/* LF */ 0x0a));
+        assert!(!mask.is_match(b'A'));
+        assert!(!mask.is_match(/* DEL */ 0x7f));
+
+        let mask = X3EchoMask::from(128);
+
+        assert!(mask.is_match(/* DEL */ 0x7f));
+        assert!(!mask.is_match(/* CR */ 0x0d));
+    }
+}
@@ -0,0 +1,334 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::x121::{X121Addr, X121Pattern};
+
+/// Access control list for incoming XOT connections.
+///
+/// Rules are evaluated in order, the first rule that matches determines the
+/// action. If no rule matches then the default action applies.
+#[derive(Debug)]
+pub struct XotAcl {
+    rules: Vec<XotAclRule>,
+    default_action: XotAclAction,
+}
+
+/// Access control list action.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum XotAclAction {
+    Allow,
+    Deny,
+}
+
+/// Access control list rule.
+///
+/// A rule matches a source IP address, and optionally the called and calling
+/// X.121 address of the incoming call using an `X121Pattern`.
+#[derive(Clone, Debug)]
+pub struct XotAclRule {
+    action: XotAclAction,
+    source: Option<IpCidr>,
+    called: Option<X121Pattern>,
+    calling: Option<X121Pattern>,
+}
+
+impl XotAcl {
+    /// Creates a new empty `XotAcl` with the default action provided.
+    pub fn new(default_action: XotAclAction) -> Self {
+        XotAcl {
+            rules: vec![],
+            default_action,
+        }
+    }
+
+    /// Adds a rule, rules are evaluated in the order they are added.
+    pub fn add(&mut self, rule: XotAclRule) {
+        self.rules.push(rule);
+    }
+
+    /// Returns `true` if a connection from the source address may be allowed,
+    /// and `false` if no call from the source address would be allowed.
+    ///
+    /// This can be used to reject a connection before the _call request_ is
+    /// received.
+    pub fn is_source_allowed(&self, source: IpAddr) -> bool {
+        for rule in self.rules.iter().filter(|r| r.is_source_match(source)) {
+            // A rule with X.121 address patterns can only be fully evaluated
+            // once the call request is received.
+            if !rule.has_addr_patterns() {
+                return rule.action == XotAclAction::Allow;
+            }
+
+            if rule.action == XotAclAction::Allow {
+                return true;
+            }
+        }
+
+        self.default_action == XotAclAction::Allow
+    }
+
+    /// Returns `true` if a call from the source address, with the called and
+    /// calling addresses provided, is allowed.
+    pub fn is_call_allowed(&self, source: IpAddr, called: &X121Addr, calling: &X121Addr) -> bool {
+        let action = self
+            .rules
+            .iter()
+            .find(|r| r.is_match(source, called, calling))
+            .map_or(self.default_action, |r| r.action);
+
+        action == XotAclAction::Allow
+    }
+}
+
+impl Default for XotAcl {
+    fn default() -> Self {
+        XotAcl::new(XotAclAction::Allow)
+    }
+}
+
+impl XotAclRule {
+    fn is_source_match(&self, source: IpAddr) -> bool {
+        self.source.as_ref().map_or(true, |s| s.contains(source))
+    }
+
+    fn has_addr_patterns(&self) -> bool {
+        self.called.is_some() || self.calling.is_some()
+    }
+
+    fn is_match(&self, source: IpAddr, called: &X121Addr, calling: &X121Addr) -> bool {
+        if !self.is_source_match(source) {
+            return false;
+        }
+
+        if let Some(ref called_pattern) = self.called {
+            if !called_pattern.is_match(called) {
+                return false;
+            }
+        }
+
+        if let Some(ref calling_pattern) = self.calling {
+            if !calling_pattern.is_match(calling) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl FromStr for XotAclRule {
+    type Err = String;
+
+    /// Parses a rule such as `allow 10.0.0.0/8 called=1234* calling=5678*`.
+    ///
+    /// The source may be an IP address, a CIDR block or `any`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split_whitespace();
+
+        let action = match parts.next().map(str::to_lowercase).as_deref() {
+            Some("allow") => XotAclAction::Allow,
+            Some("deny") => XotAclAction::Deny,
+            Some(other) => return Err(format!("invalid action: {other}")),
+            None => return Err("action required".into()),
+        };
+
+        let source = match parts.next() {
+            Some("any") => None,
+            Some(source) => Some(IpCidr::from_str(source)?),
+            None => return Err("source required".into()),
+        };
+
+        let mut called = None;
+        let mut calling = None;
+
+        for part in parts {
+            let Some((key, value)) = part.split_once('=') else {
+                return Err(format!("invalid address pattern: {part}"));
+            };
+
+            let pattern =
+                X121Pattern::from_str(value).map_err(|e| format!("invalid pattern: {e}"))?;
+
+            match key {
+                "called" => called = Some(pattern),
+                "calling" => calling = Some(pattern),
+                _ => return Err(format!("invalid address pattern: {part}")),
+            }
+        }
+
+        Ok(XotAclRule {
+            action,
+            source,
+            called,
+            calling,
+        })
+    }
+}
+
+/// IP address block, in CIDR notation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Returns `true` if the address is contained in this block.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        // Connections to a dual stack socket from IPv4 peers will have an IPv4
+        // mapped IPv6 address.
+        let addr = match addr {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+            IpAddr::V4(_) => addr,
+        };
+
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix_len));
+                let mask = mask.unwrap_or(0);
+
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix_len));
+                let mask = mask.unwrap_or(0);
+
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+
+        let addr = IpAddr::from_str(addr).map_err(|e| format!("invalid address: {e}"))?;
+
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => {
+                u8::from_str(prefix_len).map_err(|_| "invalid prefix length".to_string())?
+            }
+            None => max_prefix_len,
+        };
+
+        if prefix_len > max_prefix_len {
+            return Err(format!("prefix length out of range: {prefix_len}"));
+        }
+
+        Ok(IpCidr { addr, prefix_len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    fn addr(s: &str) -> X121Addr {
+        X121Addr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn cidr_contains() {
+        let cidr = IpCidr::from_str("10.1.0.0/16").unwrap();
+
+        assert!(cidr.contains(ip("10.1.2.3")));
+        assert!(cidr.contains(ip("::ffff:10.1.2.3")));
+        assert!(!cidr.contains(ip("10.2.0.1")));
+        assert!(!cidr.contains(ip("fe80::1")));
+
+        let cidr = IpCidr::from_str("0.0.0.0/0").unwrap();
+
+        assert!(cidr.contains(ip("192.168.1.1")));
+
+        let cidr = IpCidr::from_str("2001:db8::/32").unwrap();
+
+        assert!(cidr.contains(ip("2001:db8::1")));
+        assert!(!cidr.contains(ip("2001:db9::1")));
+
+        let cidr = IpCidr::from_str("192.168.1.1").unwrap();
+
+        assert!(cidr.contains(ip("192.168.1.1")));
+        assert!(!cidr.contains(ip("192.168.1.2")));
+    }
+
+    #[test]
+    fn cidr_from_str_invalid() {
+        assert!(IpCidr::from_str("10.0.0.0/33").is_err());
+        assert!(IpCidr::from_str("10.0.0/8").is_err());
+        assert!(IpCidr::from_str("10.0.0.0/a").is_err());
+    }
+
+    #[test]
+    fn rule_from_str() {
+        assert!(XotAclRule::from_str("allow any").is_ok());
+        assert!(XotAclRule::from_str("deny 10.0.0.0/8 called=1234* calling=5678*").is_ok());
+
+        assert!(XotAclRule::from_str("").is_err());
+        assert!(XotAclRule::from_str("allow").is_err());
+        assert!(XotAclRule::from_str("permit any").is_err());
+        assert!(XotAclRule::from_str("allow any called").is_err());
+        assert!(XotAclRule::from_str("allow any other=1234").is_err());
+        assert!(XotAclRule::from_str("allow any called=(").is_err());
+        assert!(XotAclRule::from_str("allow any called=^1234").is_err());
+    }
+
+    #[test]
+    fn is_source_allowed() {
+        let mut acl = XotAcl::new(XotAclAction::Deny);
+
+        acl.add(XotAclRule::from_str("deny 10.0.0.1").unwrap());
+        acl.add(XotAclRule::from_str("allow 10.0.0.0/8").unwrap());
+        acl.add(XotAclRule::from_str("allow 192.168.0.0/16 called=1234*").unwrap());
+
+        assert!(!acl.is_source_allowed(ip("10.0.0.1")));
+        assert!(acl.is_source_allowed(ip("10.0.0.2")));
+        assert!(acl.is_source_allowed(ip("192.168.1.1")));
+        assert!(!acl.is_source_allowed(ip("172.16.0.1")));
+    }
+
+    #[test]
+    fn is_call_allowed() {
+        let mut acl = XotAcl::new(XotAclAction::Allow);
+
+        acl.add(XotAclRule::from_str("allow 10.0.0.0/8 calling=5678*").unwrap());
+        acl.add(XotAclRule::from_str("deny any called=1234*").unwrap());
+
+        assert!(acl.is_call_allowed(ip("10.0.0.1"), &addr("1234"), &addr("5678")));
+        assert!(!acl.is_call_allowed(ip("10.0.0.1"), &addr("1234"), &addr("9999")));
+        assert!(!acl.is_call_allowed(ip("172.16.0.1"), &addr("1234"), &addr("5678")));
+        assert!(acl.is_call_allowed(ip("172.16.0.1"), &addr("4321"), &addr("5678")));
+    }
+
+    #[test]
+    fn is_call_allowed_exact() {
+        let mut acl = XotAcl::new(XotAclAction::Deny);
+
+        // The pattern must match the entire address.
+        acl.add(XotAclRule::from_str("allow any called=737411+2").unwrap());
+
+        assert!(acl.is_call_allowed(ip("10.0.0.1"), &addr("737411"), &addr("")));
+        assert!(acl.is_call_allowed(ip("10.0.0.1"), &addr("73741101"), &addr("")));
+        assert!(!acl.is_call_allowed(ip("10.0.0.1"), &addr("737411012"), &addr("")));
+        assert!(!acl.is_call_allowed(ip("10.0.0.1"), &addr("1737411"), &addr("")));
+    }
+}
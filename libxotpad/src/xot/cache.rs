@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tracing_mutex::stdsync::Mutex;
+
+use crate::xot::TCP_PORT;
+
+type LookupFn = fn(&str) -> io::Result<Vec<SocketAddr>>;
+
+/// Cache of XOT gateway address resolutions.
+///
+/// Successful resolutions are cached for the positive TTL, failed resolutions
+/// are cached for the negative TTL so that an unreachable name server does not
+/// stall every call attempt.
+pub struct XotGatewayCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    stats: Mutex<XotGatewayCacheStats>,
+    positive_ttl: Duration,
+    negative_ttl: Duration,
+    lookup: LookupFn,
+}
+
+/// XOT gateway cache statistics.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct XotGatewayCacheStats {
+    /// The number of lookups answered by a successful resolution.
+    pub hits: u64,
+
+    /// The number of lookups answered by a failed resolution.
+    pub negative_hits: u64,
+
+    /// The number of lookups that required a resolution.
+    pub misses: u64,
+
+    /// The number of resolutions that failed.
+    pub failures: u64,
+
+    /// The number of entries currently cached, including expired entries.
+    pub entries: usize,
+}
+
+enum CacheEntry {
+    Resolved(Vec<SocketAddr>, Instant),
+    Failed(io::ErrorKind, String, Instant),
+}
+
+impl XotGatewayCache {
+    /// Creates a new `XotGatewayCache` with the provided positive and negative
+    /// TTLs.
+    pub fn new(positive_ttl: Duration, negative_ttl: Duration) -> Self {
+        XotGatewayCache::with_lookup(positive_ttl, negative_ttl, lookup_gateway)
+    }
+
+    fn with_lookup(positive_ttl: Duration, negative_ttl: Duration, lookup: LookupFn) -> Self {
+        XotGatewayCache {
+            entries: Mutex::new(HashMap::new()),
+            stats: Mutex::new(XotGatewayCacheStats::default()),
+            positive_ttl,
+            negative_ttl,
+            lookup,
+        }
+    }
+
+    /// Resolves the XOT gateway to socket addresses, using a cached resolution
+    /// if available.
+    pub fn resolve(&self, gateway: &str) -> io::Result<Vec<SocketAddr>> {
+        {
+            let entries = self.entries.lock().unwrap();
+
+            match entries.get(gateway) {
+                Some(CacheEntry::Resolved(addrs, expires)) if Instant::now() < *expires => {
+                    self.stats.lock().unwrap().hits += 1;
+
+                    return Ok(addrs.clone());
+                }
+                Some(CacheEntry::Failed(kind, msg, expires)) if Instant::now() < *expires => {
+                    self.stats.lock().unwrap().negative_hits += 1;
+
+                    return Err(io::Error::new(*kind, msg.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        // The lock is not held during the lookup, concurrent misses for the same
+        // gateway will each perform a lookup and the last one wins.
+        let result = (self.lookup)(gateway);
+
+        let mut entries = self.entries.lock().unwrap();
+        let mut stats = self.stats.lock().unwrap();
+
+        stats.misses += 1;
+
+        let entry = match result {
+            Ok(ref addrs) => {
+                CacheEntry::Resolved(addrs.clone(), Instant::now() + self.positive_ttl)
+            }
+            Err(ref err) => {
+                stats.failures += 1;
+
+                CacheEntry::Failed(
+                    err.kind(),
+                    err.to_string(),
+                    Instant::now() + self.negative_ttl,
+                )
+            }
+        };
+
+        entries.insert(gateway.to_string(), entry);
+
+        result
+    }
+
+    /// Removes all cached resolutions, returning the number of entries removed.
+    pub fn flush(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+
+        let count = entries.len();
+
+        entries.clear();
+
+        count
+    }
+
+    /// Returns the cache statistics.
+    pub fn stats(&self) -> XotGatewayCacheStats {
+        let entries = self.entries.lock().unwrap();
+
+        XotGatewayCacheStats {
+            entries: entries.len(),
+            ..*self.stats.lock().unwrap()
+        }
+    }
+}
+
+impl fmt::Debug for XotGatewayCache {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("XotGatewayCache")
+            .field("positive_ttl", &self.positive_ttl)
+            .field("negative_ttl", &self.negative_ttl)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+fn lookup_gateway(gateway: &str) -> io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = (gateway, TCP_PORT).to_socket_addrs()?.collect();
+
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no addresses found for XOT gateway: {gateway}"),
+        ));
+    }
+
+    Ok(addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup_localhost(gateway: &str) -> io::Result<Vec<SocketAddr>> {
+        if gateway != "localhost" {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "not found"));
+        }
+
+        Ok(vec![SocketAddr::from(([127, 0, 0, 1], TCP_PORT))])
+    }
+
+    #[test]
+    fn resolve_hit() {
+        let cache = XotGatewayCache::with_lookup(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            lookup_localhost,
+        );
+
+        assert!(cache.resolve("localhost").is_ok());
+        assert!(cache.resolve("localhost").is_ok());
+
+        let stats = cache.stats();
+
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn resolve_negative_hit() {
+        let cache = XotGatewayCache::with_lookup(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            lookup_localhost,
+        );
+
+        let err = cache.resolve("unknown").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        let err = cache.resolve("unknown").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        let stats = cache.stats();
+
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.negative_hits, 1);
+    }
+
+    #[test]
+    fn resolve_expired() {
+        let cache = XotGatewayCache::with_lookup(Duration::ZERO, Duration::ZERO, lookup_localhost);
+
+        assert!(cache.resolve("localhost").is_ok());
+        assert!(cache.resolve("localhost").is_ok());
+        assert!(cache.resolve("unknown").is_err());
+        assert!(cache.resolve("unknown").is_err());
+
+        let stats = cache.stats();
+
+        assert_eq!(stats.misses, 4);
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.negative_hits, 0);
+    }
+
+    #[test]
+    fn flush() {
+        let cache = XotGatewayCache::with_lookup(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            lookup_localhost,
+        );
+
+        assert!(cache.resolve("localhost").is_ok());
+        assert!(cache.resolve("unknown").is_err());
+
+        assert_eq!(cache.flush(), 2);
+        assert_eq!(cache.stats().entries, 0);
+
+        assert!(cache.resolve("localhost").is_ok());
+
+        assert_eq!(cache.stats().misses, 3);
+    }
+}
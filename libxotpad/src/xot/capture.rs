@@ -0,0 +1,398 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_mutex::stdsync::Mutex;
+
+/// Packet capture of XOT traffic.
+///
+/// Captured traffic is written in the pcap format, each XOT link is recorded as
+/// a synthesized TCP connection between the local and remote socket addresses
+/// so that it can be decoded by the Wireshark XOT dissector.
+#[derive(Clone)]
+pub struct XotCapture(Arc<Mutex<CaptureWriter>>);
+
+struct CaptureWriter {
+    writer: Box<dyn Write + Send>,
+    ip_id: u16,
+}
+
+impl XotCapture {
+    /// Creates a new `XotCapture` writing to the file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::create(path)?;
+
+        XotCapture::new(BufWriter::new(file))
+    }
+
+    /// Creates a new `XotCapture` writing to `writer`.
+    pub fn new<W: Write + Send + 'static>(mut writer: W) -> io::Result<Self> {
+        write_file_header(&mut writer)?;
+
+        writer.flush()?;
+
+        Ok(XotCapture(Arc::new(Mutex::new(CaptureWriter {
+            writer: Box::new(writer),
+            ip_id: 0,
+        }))))
+    }
+
+    fn write_segment(&self, segment: &Segment, payload: &[u8]) -> io::Result<()> {
+        let mut capture = self.0.lock().unwrap();
+
+        capture.ip_id = capture.ip_id.wrapping_add(1);
+
+        let packet = encode_ip_packet(segment, capture.ip_id, payload);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let len = u32::try_from(packet.len()).unwrap();
+
+        let mut record = Vec::with_capacity(16 + packet.len());
+
+        record.extend_from_slice(&(timestamp.as_secs() as u32).to_le_bytes());
+        record.extend_from_slice(&timestamp.subsec_micros().to_le_bytes());
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&packet);
+
+        capture.writer.write_all(&record)?;
+        capture.writer.flush()
+    }
+}
+
+impl fmt::Debug for XotCapture {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("XotCapture").finish_non_exhaustive()
+    }
+}
+
+/// Capture state for a single XOT link, shared by both halves of a split link.
+pub(crate) struct LinkCapture {
+    capture: XotCapture,
+    local_addr: SocketAddr,
+    remote_addr: SocketAddr,
+    local_seq: u32,
+    remote_seq: u32,
+}
+
+/// Direction of captured traffic.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum Direction {
+    Send,
+    Recv,
+}
+
+struct Segment {
+    src: SocketAddr,
+    dst: SocketAddr,
+    seq: u32,
+    ack: u32,
+}
+
+impl LinkCapture {
+    pub(crate) fn new(
+        capture: XotCapture,
+        local_addr: SocketAddr,
+        remote_addr: SocketAddr,
+    ) -> Self {
+        LinkCapture {
+            capture,
+            local_addr,
+            remote_addr,
+            local_seq: 1,
+            remote_seq: 1,
+        }
+    }
+
+    /// Records data sent or received on the link.
+    pub(crate) fn record(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let len = data.len() as u32;
+
+        let segment = match direction {
+            Direction::Send => Segment {
+                src: self.local_addr,
+                dst: self.remote_addr,
+                seq: self.local_seq,
+                ack: self.remote_seq,
+            },
+            Direction::Recv => Segment {
+                src: self.remote_addr,
+                dst: self.local_addr,
+                seq: self.remote_seq,
+                ack: self.local_seq,
+            },
+        };
+
+        match direction {
+            Direction::Send => self.local_seq = self.local_seq.wrapping_add(len),
+            Direction::Recv => self.remote_seq = self.remote_seq.wrapping_add(len),
+        }
+
+        self.capture.write_segment(&segment, data)
+    }
+}
+
+const PCAP_MAGIC: u32 = 0xa1b2_c3d4;
+const PCAP_SNAPLEN: u32 = 65535;
+const LINKTYPE_RAW: u32 = 101;
+
+const TCP_HEADER_LEN: usize = 20;
+const TCP_FLAGS_PSH_ACK: u8 = 0x18;
+const IP_PROTOCOL_TCP: u8 = 6;
+
+fn write_file_header<W: Write>(writer: &mut W) -> io::Result<()> {
+    let mut header = Vec::with_capacity(24);
+
+    header.extend_from_slice(&PCAP_MAGIC.to_le_bytes());
+    header.extend_from_slice(&2_u16.to_le_bytes()); // Major version
+    header.extend_from_slice(&4_u16.to_le_bytes()); // Minor version
+    header.extend_from_slice(&0_i32.to_le_bytes()); // GMT offset
+    header.extend_from_slice(&0_u32.to_le_bytes()); // Timestamp accuracy
+    header.extend_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+    header.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+
+    writer.write_all(&header)
+}
+
+fn encode_ip_packet(segment: &Segment, ip_id: u16, payload: &[u8]) -> Vec<u8> {
+    let src = segment.src.ip();
+    let dst = segment.dst.ip();
+
+    // Both addresses must be of the same family, IPv4 mapped IPv6 addresses are
+    // converted to IPv4 where possible.
+    match (to_canonical(src), to_canonical(dst)) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => {
+            let tcp = encode_tcp_segment(segment, &src.octets(), &dst.octets(), payload);
+
+            let mut packet = Vec::with_capacity(20 + tcp.len());
+
+            packet.push(0x45); // Version 4, header length 5 words
+            packet.push(0x00);
+            packet.extend_from_slice(&((20 + tcp.len()) as u16).to_be_bytes());
+            packet.extend_from_slice(&ip_id.to_be_bytes());
+            packet.extend_from_slice(&0x4000_u16.to_be_bytes()); // Don't fragment
+            packet.push(64); // TTL
+            packet.push(IP_PROTOCOL_TCP);
+            packet.extend_from_slice(&[0, 0]); // Checksum
+            packet.extend_from_slice(&src.octets());
+            packet.extend_from_slice(&dst.octets());
+
+            let checksum = checksum(&[&packet]);
+
+            packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+            packet.extend_from_slice(&tcp);
+
+            packet
+        }
+        (src, dst) => {
+            let src = to_ipv6(src).octets();
+            let dst = to_ipv6(dst).octets();
+
+            let tcp = encode_tcp_segment(segment, &src, &dst, payload);
+
+            let mut packet = Vec::with_capacity(40 + tcp.len());
+
+            packet.extend_from_slice(&0x6000_0000_u32.to_be_bytes()); // Version 6
+            packet.extend_from_slice(&(tcp.len() as u16).to_be_bytes());
+            packet.push(IP_PROTOCOL_TCP);
+            packet.push(64); // Hop limit
+            packet.extend_from_slice(&src);
+            packet.extend_from_slice(&dst);
+            packet.extend_from_slice(&tcp);
+
+            packet
+        }
+    }
+}
+
+fn encode_tcp_segment(segment: &Segment, src: &[u8], dst: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut tcp = Vec::with_capacity(TCP_HEADER_LEN + payload.len());
+
+    tcp.extend_from_slice(&segment.src.port().to_be_bytes());
+    tcp.extend_from_slice(&segment.dst.port().to_be_bytes());
+    tcp.extend_from_slice(&segment.seq.to_be_bytes());
+    tcp.extend_from_slice(&segment.ack.to_be_bytes());
+    tcp.push((TCP_HEADER_LEN as u8 / 4) << 4);
+    tcp.push(TCP_FLAGS_PSH_ACK);
+    tcp.extend_from_slice(&u16::MAX.to_be_bytes()); // Window
+    tcp.extend_from_slice(&[0, 0]); // Checksum
+    tcp.extend_from_slice(&[0, 0]); // Urgent pointer
+    tcp.extend_from_slice(payload);
+
+    let tcp_len = tcp.len() as u32;
+
+    // The checksum covers a "pseudo header" containing the IP addresses.
+    let pseudo_header = if src.len() == 4 {
+        let mut header = Vec::with_capacity(12);
+
+        header.extend_from_slice(src);
+        header.extend_from_slice(dst);
+        header.push(0);
+        header.push(IP_PROTOCOL_TCP);
+        header.extend_from_slice(&(tcp_len as u16).to_be_bytes());
+
+        header
+    } else {
+        let mut header = Vec::with_capacity(40);
+
+        header.extend_from_slice(src);
+        header.extend_from_slice(dst);
+        header.extend_from_slice(&tcp_len.to_be_bytes());
+        header.extend_from_slice(&[0, 0, 0, IP_PROTOCOL_TCP]);
+
+        header
+    };
+
+    let checksum = checksum(&[&pseudo_header, &tcp]);
+
+    tcp[16..18].copy_from_slice(&checksum.to_be_bytes());
+
+    tcp
+}
+
+/// Calculates the Internet checksum, as specified in IETF RFC 1071.
+fn checksum(bufs: &[&[u8]]) -> u16 {
+    let mut sum: u32 = 0;
+
+    // All but the last buffer are expected to have an even length.
+    for buf in bufs {
+        for pair in buf.chunks(2) {
+            let high = u32::from(pair[0]) << 8;
+            let low = if pair.len() > 1 {
+                u32::from(pair[1])
+            } else {
+                0
+            };
+
+            sum += high | low;
+        }
+    }
+
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+fn to_canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        IpAddr::V4(_) => addr,
+    }
+}
+
+fn to_ipv6(addr: IpAddr) -> std::net::Ipv6Addr {
+    match addr {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped(),
+        IpAddr::V6(v6) => v6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn checksum_example() {
+        // Example from IETF RFC 1071.
+        let buf = b"\x00\x01\xf2\x03\xf4\xf5\xf6\xf7";
+
+        assert_eq!(checksum(&[buf]), !0xddf2);
+    }
+
+    #[test]
+    fn record_ipv4() {
+        let buf = SharedBuf::default();
+
+        let capture = XotCapture::new(buf.clone()).unwrap();
+
+        let mut link_capture = LinkCapture::new(
+            capture,
+            SocketAddr::from_str("10.0.0.1:50000").unwrap(),
+            SocketAddr::from_str("10.0.0.2:1998").unwrap(),
+        );
+
+        let xot_packet = b"\x00\x00\x00\x03\x10\x01\xe5";
+
+        link_capture.record(Direction::Send, xot_packet).unwrap();
+        link_capture.record(Direction::Recv, xot_packet).unwrap();
+
+        let buf = buf.0.lock().unwrap();
+
+        // File header, and 2 records each containing 20 bytes of IPv4 header and
+        // 20 bytes of TCP header.
+        assert_eq!(buf.len(), 24 + 2 * (16 + 20 + 20 + xot_packet.len()));
+
+        assert_eq!(&buf[0..4], b"\xd4\xc3\xb2\xa1");
+        assert_eq!(&buf[20..24], &LINKTYPE_RAW.to_le_bytes());
+
+        let packet = &buf[24 + 16..24 + 16 + 47];
+
+        // A valid IPv4 header checksums to zero.
+        assert_eq!(checksum(&[&packet[..20]]), 0);
+
+        assert_eq!(&packet[12..16], &[10, 0, 0, 1]); // Source address
+        assert_eq!(&packet[16..20], &[10, 0, 0, 2]); // Destination address
+        assert_eq!(&packet[20..22], &50000_u16.to_be_bytes()); // Source port
+        assert_eq!(&packet[22..24], &1998_u16.to_be_bytes()); // Destination port
+        assert_eq!(&packet[24..28], &1_u32.to_be_bytes()); // Sequence
+        assert_eq!(&packet[40..], xot_packet);
+
+        let packet = &buf[24 + 16 + 47 + 16..];
+
+        assert_eq!(&packet[12..16], &[10, 0, 0, 2]); // Source address
+        assert_eq!(&packet[24..28], &1_u32.to_be_bytes()); // Sequence
+        assert_eq!(&packet[28..32], &8_u32.to_be_bytes()); // Acknowledgement
+    }
+
+    #[test]
+    fn record_ipv6() {
+        let buf = SharedBuf::default();
+
+        let capture = XotCapture::new(buf.clone()).unwrap();
+
+        let mut link_capture = LinkCapture::new(
+            capture,
+            SocketAddr::from_str("[2001:db8::1]:50000").unwrap(),
+            SocketAddr::from_str("[2001:db8::2]:1998").unwrap(),
+        );
+
+        let xot_packet = b"\x00\x00\x00\x03\x10\x01\xe5";
+
+        link_capture.record(Direction::Send, xot_packet).unwrap();
+
+        let buf = buf.0.lock().unwrap();
+
+        assert_eq!(buf.len(), 24 + 16 + 40 + 20 + xot_packet.len());
+
+        let packet = &buf[24 + 16..];
+
+        assert_eq!(packet[0] >> 4, 6);
+        assert_eq!(&packet[60..], xot_packet);
+    }
+}
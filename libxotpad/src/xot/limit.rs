@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing_mutex::stdsync::Mutex;
+
+/// Limits for incoming XOT connections.
+#[derive(Copy, Clone, Debug)]
+pub struct XotListenerLimits {
+    /// The maximum number of connections waiting for a _call request_.
+    pub max_pending: usize,
+
+    /// The maximum rate of connections from a single source address.
+    pub rate: Option<XotRateLimit>,
+}
+
+/// Connection rate limit.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct XotRateLimit {
+    /// The maximum number of connections within the interval.
+    pub count: usize,
+
+    /// The interval.
+    pub interval: Duration,
+}
+
+impl FromStr for XotRateLimit {
+    type Err = String;
+
+    /// Parses a rate limit such as `10/60`, meaning 10 connections every 60
+    /// seconds.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let Some((count, interval)) = s.split_once('/') else {
+            return Err("expected COUNT/SECONDS".into());
+        };
+
+        let count = usize::from_str(count.trim()).map_err(|_| "invalid count".to_string())?;
+        let interval =
+            u64::from_str(interval.trim()).map_err(|_| "invalid interval".to_string())?;
+
+        if count == 0 || interval == 0 {
+            return Err("count and interval must be greater than zero".into());
+        }
+
+        Ok(XotRateLimit {
+            count,
+            interval: Duration::from_secs(interval),
+        })
+    }
+}
+
+/// Enforces `XotListenerLimits` on incoming XOT connections.
+#[derive(Debug)]
+pub struct XotConnectionLimiter {
+    limits: XotListenerLimits,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    pending: usize,
+    history: HashMap<IpAddr, VecDeque<Instant>>,
+}
+
+/// Admission of a pending XOT connection, the pending connection count is
+/// decremented when dropped.
+#[derive(Debug)]
+pub struct XotPendingConnection(Arc<XotConnectionLimiter>);
+
+impl XotConnectionLimiter {
+    pub fn new(limits: XotListenerLimits) -> Arc<Self> {
+        Arc::new(XotConnectionLimiter {
+            limits,
+            state: Mutex::new(LimiterState::default()),
+        })
+    }
+
+    /// Attempts to admit a new connection from the source address, returning
+    /// `None` if a limit has been reached.
+    pub fn admit(self: &Arc<Self>, source: IpAddr) -> Option<XotPendingConnection> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.pending >= self.limits.max_pending {
+            return None;
+        }
+
+        if let Some(rate) = self.limits.rate {
+            let now = Instant::now();
+
+            // Forget about connections outside of the interval, for all sources
+            // so that the history does not grow without bound.
+            state.history.retain(|_, times| {
+                while times
+                    .front()
+                    .is_some_and(|&t| now.duration_since(t) >= rate.interval)
+                {
+                    times.pop_front();
+                }
+
+                !times.is_empty()
+            });
+
+            let times = state.history.entry(source).or_default();
+
+            if times.len() >= rate.count {
+                return None;
+            }
+
+            times.push_back(now);
+        }
+
+        state.pending += 1;
+
+        Some(XotPendingConnection(Arc::clone(self)))
+    }
+
+    /// Returns the number of pending connections.
+    pub fn pending(&self) -> usize {
+        self.state.lock().unwrap().pending
+    }
+}
+
+impl Drop for XotPendingConnection {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().pending -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn rate_limit_from_str() {
+        assert_eq!(
+            XotRateLimit::from_str("10/60"),
+            Ok(XotRateLimit {
+                count: 10,
+                interval: Duration::from_secs(60)
+            })
+        );
+
+        assert!(XotRateLimit::from_str("10").is_err());
+        assert!(XotRateLimit::from_str("0/60").is_err());
+        assert!(XotRateLimit::from_str("10/0").is_err());
+        assert!(XotRateLimit::from_str("a/b").is_err());
+    }
+
+    #[test]
+    fn admit_max_pending() {
+        let limiter = XotConnectionLimiter::new(XotListenerLimits {
+            max_pending: 2,
+            rate: None,
+        });
+
+        let a = limiter.admit(ip("10.0.0.1"));
+        let b = limiter.admit(ip("10.0.0.2"));
+
+        assert!(a.is_some());
+        assert!(b.is_some());
+        assert!(limiter.admit(ip("10.0.0.3")).is_none());
+        assert_eq!(limiter.pending(), 2);
+
+        drop(a);
+
+        assert_eq!(limiter.pending(), 1);
+        assert!(limiter.admit(ip("10.0.0.3")).is_some());
+    }
+
+    #[test]
+    fn admit_rate_limit() {
+        let limiter = XotConnectionLimiter::new(XotListenerLimits {
+            max_pending: 10,
+            rate: Some(XotRateLimit {
+                count: 2,
+                interval: Duration::from_secs(60),
+            }),
+        });
+
+        assert!(limiter.admit(ip("10.0.0.1")).is_some());
+        assert!(limiter.admit(ip("10.0.0.1")).is_some());
+        assert!(limiter.admit(ip("10.0.0.1")).is_none());
+        assert!(limiter.admit(ip("10.0.0.2")).is_some());
+    }
+}
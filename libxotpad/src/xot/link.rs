@@ -1,13 +1,48 @@
 use bytes::{BufMut, Bytes, BytesMut};
+use socket2::{SockRef, TcpKeepalive};
 use std::io::{self, Read, Write};
 use std::net::{Shutdown, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info_span, trace, warn, Span};
+use tracing_mutex::stdsync::Mutex;
 
 use crate::x25;
+use crate::xot::capture::{Direction, LinkCapture, XotCapture};
 
 /// XOT link allowing X.25 packets to be transmitted over a `TcpStream`.
 pub struct XotLink {
     stream: TcpStream,
     recv_buf: BytesMut,
+    capture: Option<Arc<Mutex<LinkCapture>>>,
+    stats: Arc<Mutex<XotLinkStats>>,
+    span: Span,
+}
+
+/// XOT link statistics.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct XotLinkStats {
+    /// The number of X.25 packets sent.
+    pub packets_sent: u64,
+
+    /// The number of X.25 packets received.
+    pub packets_received: u64,
+
+    /// The number of bytes sent, including the XOT header.
+    pub bytes_sent: u64,
+
+    /// The number of bytes received, including the XOT header.
+    pub bytes_received: u64,
+}
+
+/// TCP keepalive settings for an `XotLink`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct XotKeepalive {
+    /// The time the link must be idle before keepalive probes are sent.
+    pub time: Duration,
+
+    /// The interval between keepalive probes.
+    pub interval: Duration,
 }
 
 impl XotLink {
@@ -17,9 +52,63 @@ impl XotLink {
     pub fn new(stream: TcpStream) -> Self {
         stream.set_nodelay(true).expect("set_nodelay error");
 
+        let span = info_span!(
+            "xot_link",
+            local = tracing::field::Empty,
+            remote = tracing::field::Empty
+        );
+
+        if let Ok(local_addr) = stream.local_addr() {
+            span.record("local", tracing::field::display(local_addr));
+        }
+
+        if let Ok(remote_addr) = stream.peer_addr() {
+            span.record("remote", tracing::field::display(remote_addr));
+        }
+
         XotLink {
             stream,
             recv_buf: BytesMut::new(),
+            capture: None,
+            stats: Arc::new(Mutex::new(XotLinkStats::default())),
+            span,
+        }
+    }
+
+    /// Sets the packet capture for this link, or disables capture if `None`.
+    ///
+    /// All data sent and received on the link, after this is called, is written
+    /// to the capture.
+    pub fn set_capture(&mut self, capture: Option<XotCapture>) -> io::Result<()> {
+        self.capture = match capture {
+            Some(capture) => {
+                let local_addr = self.stream.local_addr()?;
+                let remote_addr = self.stream.peer_addr()?;
+
+                let link_capture = LinkCapture::new(capture, local_addr, remote_addr);
+
+                Some(Arc::new(Mutex::new(link_capture)))
+            }
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    /// Sets the TCP keepalive for this link, or disables keepalive if `None`.
+    ///
+    /// If the peer stops responding to keepalive probes the link will fail,
+    /// with an error, rather than waiting indefinitely.
+    pub fn set_keepalive(&self, keepalive: Option<XotKeepalive>) -> io::Result<()> {
+        let socket = SockRef::from(&self.stream);
+
+        match keepalive {
+            Some(XotKeepalive { time, interval }) => {
+                let keepalive = TcpKeepalive::new().with_time(time).with_interval(interval);
+
+                socket.set_tcp_keepalive(&keepalive)
+            }
+            None => socket.set_keepalive(false),
         }
     }
 
@@ -30,7 +119,20 @@ impl XotLink {
         encode(x25_packet, &mut buf)
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
 
-        self.stream.write_all(&buf)
+        self.stream.write_all(&buf)?;
+
+        trace!(parent: &self.span, len = x25_packet.len(), "XOT packet sent");
+
+        {
+            let mut stats = self.stats.lock().unwrap();
+
+            stats.packets_sent += 1;
+            stats.bytes_sent += buf.len() as u64;
+        }
+
+        self.record(Direction::Send, &buf);
+
+        Ok(())
     }
 
     /// Receives an X.25 packet.
@@ -40,6 +142,15 @@ impl XotLink {
                 .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
 
             if let Some(x25_packet) = x25_packet {
+                trace!(parent: &self.span, len = x25_packet.len(), "XOT packet received");
+
+                {
+                    let mut stats = self.stats.lock().unwrap();
+
+                    stats.packets_received += 1;
+                    stats.bytes_received += (XOT_HEADER_LEN + x25_packet.len()) as u64;
+                }
+
                 return Ok(x25_packet);
             }
 
@@ -51,10 +162,17 @@ impl XotLink {
                 return Err(io::Error::from(io::ErrorKind::ConnectionReset));
             }
 
+            self.record(Direction::Recv, &buf[..len]);
+
             self.recv_buf.extend(&buf[..len]);
         }
     }
 
+    /// Returns the statistics for this link.
+    pub fn stats(&self) -> XotLinkStats {
+        *self.stats.lock().unwrap()
+    }
+
     /// Unwraps this `XotLink`, returning the underlying `TcpStream`.
     ///
     /// Note that any leftover data in the internal buffer is lost. Therefore, a
@@ -63,9 +181,40 @@ impl XotLink {
         self.stream
     }
 
+    /// Creates a new `XotLink` sharing the underlying `TcpStream`, capture and
+    /// statistics with this one.
+    ///
+    /// The new link has an empty internal buffer, it is expected that only one
+    /// of the links is used to receive.
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
+        Ok(XotLink {
+            stream: self.stream.try_clone()?,
+            recv_buf: BytesMut::new(),
+            capture: self.capture.clone(),
+            stats: Arc::clone(&self.stats),
+            span: self.span.clone(),
+        })
+    }
+
+    /// Returns the tracing span for this link.
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
     pub(crate) fn shutdown(&mut self) -> io::Result<()> {
         self.stream.shutdown(Shutdown::Both)
     }
+
+    fn record(&self, direction: Direction, data: &[u8]) {
+        let Some(ref capture) = self.capture else {
+            return;
+        };
+
+        // A capture failure should not affect the link itself.
+        if let Err(err) = capture.lock().unwrap().record(direction, data) {
+            warn!(parent: &self.span, error = %err, "packet capture failed");
+        }
+    }
 }
 
 const XOT_HEADER_LEN: usize = 4;
@@ -11,25 +11,29 @@ use std::net::TcpStream;
 
 use crate::x121::X121Addr;
 
+mod acl;
+mod cache;
+mod capture;
+mod limit;
 mod link;
 mod resolver;
 
-pub use self::link::XotLink;
+pub use self::acl::{IpCidr, XotAcl, XotAclAction, XotAclRule};
+pub use self::cache::{XotGatewayCache, XotGatewayCacheStats};
+pub use self::capture::XotCapture;
+pub use self::limit::{
+    XotConnectionLimiter, XotListenerLimits, XotPendingConnection, XotRateLimit,
+};
+pub use self::link::{XotKeepalive, XotLink, XotLinkStats};
 pub use self::resolver::XotResolver;
 
 /// Registered XOT TCP port number.
 pub const TCP_PORT: u16 = 1998;
 
 pub fn connect(addr: &X121Addr, resolver: &XotResolver) -> io::Result<XotLink> {
-    let Some(xot_gateway) = resolver.lookup(addr) else {
-        // TODO: HostUnreachable...
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "no XOT gateway found".to_owned(),
-        ));
-    };
-
-    let tcp_stream = TcpStream::connect((xot_gateway, TCP_PORT))?;
+    let xot_gateway_addrs = resolver.resolve(addr)?;
+
+    let tcp_stream = TcpStream::connect(&xot_gateway_addrs[..])?;
 
     let xot_link = XotLink::new(tcp_stream);
 
@@ -1,35 +1,69 @@
-use regex::{Captures, Regex};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
 
-use crate::x121::X121Addr;
+use crate::x121::{X121Addr, X121Pattern};
+use crate::xot::{XotGatewayCache, TCP_PORT};
 
 #[derive(Debug)]
 pub struct XotResolver {
-    rules: Vec<(Regex, String)>,
+    rules: Vec<(X121Pattern, String)>,
+    cache: Option<XotGatewayCache>,
 }
 
 impl XotResolver {
     pub fn new() -> Self {
-        XotResolver { rules: vec![] }
+        XotResolver {
+            rules: vec![],
+            cache: None,
+        }
+    }
+
+    /// Sets the cache used when resolving XOT gateway addresses.
+    pub fn set_cache(&mut self, cache: Option<XotGatewayCache>) {
+        self.cache = cache;
+    }
+
+    /// Returns the cache used when resolving XOT gateway addresses, if any.
+    pub fn cache(&self) -> Option<&XotGatewayCache> {
+        self.cache.as_ref()
     }
 
+    /// Adds a rule, routing addresses matching the `X121Pattern` to the gateway.
+    ///
+    /// The gateway may refer to digits captured by the pattern, such as `\1`
+    /// for the first group.
     pub fn add(&mut self, x25_addr: &str, gateway: &str) -> Result<(), String> {
-        let regex = Regex::new(x25_addr).map_err(|e| format!("invalid regex: {e}"))?;
+        let pattern =
+            X121Pattern::from_str(x25_addr).map_err(|e| format!("invalid pattern: {e}"))?;
 
-        self.rules.push((regex, gateway.into()));
+        self.rules.push((pattern, gateway.into()));
 
         Ok(())
     }
 
     pub fn lookup(&self, x25_addr: &X121Addr) -> Option<String> {
-        let x25_addr = x25_addr.to_string();
+        self.rules.iter().find_map(|(pattern, gateway)| {
+            let m = pattern.matches(x25_addr)?;
+
+            Some(template_replace(gateway, m.captures()))
+        })
+    }
+
+    /// Resolves the XOT gateway socket addresses for an X.121 address.
+    pub fn resolve(&self, x25_addr: &X121Addr) -> io::Result<Vec<SocketAddr>> {
+        let Some(xot_gateway) = self.lookup(x25_addr) else {
+            // TODO: HostUnreachable...
+            return Err(io::Error::other("no XOT gateway found"));
+        };
 
-        for (regex, gateway) in &self.rules {
-            if let Some(captures) = regex.captures(&x25_addr) {
-                return Some(template_replace(gateway, &captures));
-            }
+        if let Some(ref cache) = self.cache {
+            return cache.resolve(&xot_gateway);
         }
 
-        None
+        Ok((xot_gateway.as_str(), TCP_PORT)
+            .to_socket_addrs()?
+            .collect())
     }
 }
 
@@ -39,13 +73,13 @@ impl Default for XotResolver {
     }
 }
 
-fn template_replace(template: &str, captures: &Captures) -> String {
+fn template_replace(template: &str, captures: &[&str]) -> String {
     let mut value = template.to_string();
 
-    for (index, replacement) in captures.iter().enumerate().skip(1) {
-        let pattern = format!("\\{index}");
+    for (index, replacement) in captures.iter().enumerate() {
+        let pattern = format!("\\{}", index + 1);
 
-        value = value.replace(&pattern, replacement.unwrap().into());
+        value = value.replace(&pattern, replacement);
     }
 
     value
@@ -61,7 +95,7 @@ mod tests {
     fn lookup_hit_with_default() {
         let mut resolver = XotResolver::new();
 
-        let _ = resolver.add(".*", "gateway");
+        let _ = resolver.add("*", "gateway");
 
         let x25_addr = X121Addr::from_str("12345678").unwrap();
 
@@ -72,7 +106,7 @@ mod tests {
     fn lookup_hit_with_replacement() {
         let mut resolver = XotResolver::new();
 
-        let _ = resolver.add("^(...)(...)", "\\2.\\1.x25.org");
+        let _ = resolver.add("(XXX)(XXX)*", "\\2.\\1.x25.org");
 
         let x25_addr = X121Addr::from_str("12345678").unwrap();
 
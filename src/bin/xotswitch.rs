@@ -0,0 +1,77 @@
+use clap::Parser;
+use libxotpad::switch::X25Switch;
+use libxotpad::xot::{self, XotResolver};
+use std::io;
+use std::net::TcpListener;
+use std::sync::Arc;
+use tracing::Level;
+
+fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    init_tracing(&args);
+
+    let mut resolver = XotResolver::new();
+
+    for (pattern, destination) in &args.routes {
+        resolver
+            .add(pattern, destination)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    }
+
+    let listener = TcpListener::bind((args.xot_bind_addr.as_str(), args.xot_port))?;
+
+    let switch = Arc::new(X25Switch::new(resolver));
+
+    switch.serve(listener)
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "X.25 switch for XOT")]
+struct Args {
+    /// Bind address for incoming XOT connections.
+    #[arg(
+        short = 'b',
+        long = "bind",
+        default_value = "0.0.0.0",
+        value_name = "ADDRESS"
+    )]
+    xot_bind_addr: String,
+
+    /// Port for incoming XOT connections.
+    #[arg(long = "port", default_value_t = xot::TCP_PORT, value_name = "PORT")]
+    xot_port: u16,
+
+    /// Route, such as "1234*=gateway" or "5678+2=127.0.0.1:2001".
+    ///
+    /// Routes are evaluated in order, calls that match no route are cleared.
+    #[arg(short = 'r', long = "route", value_name = "PATTERN=DESTINATION", value_parser = parse_route)]
+    routes: Vec<(String, String)>,
+
+    /// Increase logging verbosity, may be repeated.
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+fn parse_route(s: &str) -> Result<(String, String), String> {
+    let Some((pattern, destination)) = s.rsplit_once('=') else {
+        return Err("expected PATTERN=DESTINATION".into());
+    };
+
+    Ok((pattern.to_string(), destination.to_string()))
+}
+
+fn init_tracing(args: &Args) {
+    let level = match args.verbose {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_thread_names(true)
+        .with_writer(io::stderr)
+        .init();
+}
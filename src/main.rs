@@ -1,30 +1,64 @@
 use clap::Parser;
-use libxotpad::pad::PadParams;
-use libxotpad::x121::X121Addr;
-use libxotpad::x25::{X25Modulo, X25Params};
-use libxotpad::x3::{X3Echo, X3Editing, X3Forward, X3Idle, X3LfInsert};
-use libxotpad::xot::{self, XotResolver};
-use std::collections::HashMap;
-use std::io;
+use libxotpad::x121::{X121Addr, X121Pattern};
+use libxotpad::x25::{X25AddrFormat, X25Modulo, X25Params, X25Role};
+use libxotpad::xot::{
+    self, XotAcl, XotAclRule, XotCapture, XotConnectionLimiter, XotGatewayCache, XotKeepalive,
+    XotListenerLimits, XotRateLimit, XotResolver,
+};
+use std::fs::{self, File};
+use std::io::{self, Write};
 use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
 use std::time::Duration;
+use tracing::Level;
 
-use xotpad::user_pad;
+use xotpad::user_pad::{self, LinkOptions, Listener};
 use xotpad::x28::X28Selection;
-use xotpad::x3::{UserPadParams, X3CharDelete, X3LineDelete, X3LineDisplay};
+use xotpad::x3::{load_profiles, standard_profiles, X3Profiles};
 
 fn main() -> io::Result<()> {
     let args = Args::parse();
 
-    let config = load_config(&args);
+    init_tracing(&args)?;
+
+    let config = match load_config(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    };
 
     if config.x25_params.addr.is_null() {
         eprintln!("warning: local address is null, use the --address option to specify an address");
     }
 
     let listener = if args.should_listen {
-        if let Ok(listener) = TcpListener::bind((args.xot_bind_addr.as_str(), xot::TCP_PORT)) {
-            Some(listener)
+        if let Ok(tcp_listener) = TcpListener::bind((args.xot_bind_addr.as_str(), xot::TCP_PORT)) {
+            let mut acl = XotAcl::default();
+
+            for rule in &args.acl_rules {
+                acl.add(rule.clone());
+            }
+
+            let limiter = XotConnectionLimiter::new(XotListenerLimits {
+                max_pending: args.max_pending,
+                rate: args.rate_limit,
+            });
+
+            let called = args
+                .called_pattern
+                .clone()
+                .unwrap_or_else(|| X121Pattern::with_subaddr(&config.x25_params.addr, None));
+
+            Some(Listener {
+                tcp_listener,
+                called,
+                acl,
+                limiter,
+            })
         } else {
             println!("unable to bind... will not listen!");
             None
@@ -33,17 +67,29 @@ fn main() -> io::Result<()> {
         None
     };
 
+    let capture = match args.capture_file {
+        Some(ref path) => Some(XotCapture::create(path)?),
+        None => None,
+    };
+
+    let keepalive = args.keepalive.map(|seconds| XotKeepalive {
+        time: Duration::from_secs(seconds),
+        interval: Duration::from_secs(seconds),
+    });
+
+    let link_options = LinkOptions { capture, keepalive };
+
     user_pad::run(
         &config.x25_params,
         &config.x3_profiles,
         &config.resolver,
-        config.x3_profile,
+        &config.x3_profile,
         listener,
+        link_options,
         &args.selection,
     )
 }
 
-// -c, --config <FILE>          config file
 // -P, --x25-profile <PROFILE>  X.25 profile
 // -s, --serve                  SERVE!
 #[derive(Parser, Debug)]
@@ -66,7 +112,11 @@ struct Args {
     )]
     xot_bind_addr: String,
 
-    /// X.3 profile.
+    /// Configuration file, defining X.3 profiles.
+    #[arg(short = 'c', long = "config", value_name = "FILE")]
+    config_file: Option<PathBuf>,
+
+    /// X.3 profile, such as "simple" or "transparent".
     #[arg(
         short = 'p',
         long = "x3-profile",
@@ -75,23 +125,83 @@ struct Args {
     )]
     x3_profile: String,
 
+    /// Time to cache resolved XOT gateway addresses, in seconds.
+    #[arg(long = "resolver-ttl", default_value_t = 300, value_name = "SECONDS")]
+    resolver_ttl: u64,
+
+    /// Time to cache failed XOT gateway resolutions, in seconds.
+    #[arg(
+        long = "resolver-negative-ttl",
+        default_value_t = 30,
+        value_name = "SECONDS"
+    )]
+    resolver_negative_ttl: u64,
+
+    /// X.25 role, either DTE or DCE, used to resolve call collisions.
+    #[arg(long = "x25-role", default_value = "dte", value_name = "ROLE")]
+    x25_role: X25Role,
+
+    /// Use the TOA/NPI address format for outgoing calls.
+    #[arg(long = "toa-npi")]
+    toa_npi: bool,
+
     /// Listen for incoming calls.
     #[arg(short = 'l', long = "listen")]
     should_listen: bool,
 
+    /// Called address pattern for incoming calls, such as "73741100+2".
+    ///
+    /// Defaults to the local address followed by any subaddress.
+    #[arg(long = "called", value_name = "PATTERN")]
+    called_pattern: Option<X121Pattern>,
+
+    /// Access list rule for incoming calls, such as "allow 10.0.0.0/8 called=1234*".
+    ///
+    /// Rules are evaluated in order, calls that match no rule are allowed.
+    #[arg(long = "acl", value_name = "RULE")]
+    acl_rules: Vec<XotAclRule>,
+
+    /// Maximum number of incoming connections waiting for a call.
+    #[arg(long = "max-pending", default_value_t = 16, value_name = "COUNT")]
+    max_pending: usize,
+
+    /// Maximum rate of incoming connections from a single source.
+    #[arg(long = "rate-limit", value_name = "COUNT/SECONDS")]
+    rate_limit: Option<XotRateLimit>,
+
+    /// Enable TCP keepalive on XOT links, probing after the link is idle.
+    #[arg(long = "keepalive", value_name = "SECONDS")]
+    keepalive: Option<u64>,
+
+    /// Probe idle calls with a receive ready packet, to detect a dead peer.
+    #[arg(long = "idle-probe", value_name = "SECONDS")]
+    idle_probe: Option<u64>,
+
+    /// Write a packet capture of XOT traffic to a pcap file.
+    #[arg(long = "capture", value_name = "FILE")]
+    capture_file: Option<PathBuf>,
+
+    /// Increase logging verbosity, may be repeated.
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Write log messages to a file, instead of the terminal.
+    #[arg(long = "log-file", value_name = "FILE")]
+    log_file: Option<PathBuf>,
+
     /// X.28 selection.
     #[arg(value_name = "SELECTION", conflicts_with = "should_listen")]
     selection: Option<X28Selection>,
 }
 
-struct Config<'a> {
+struct Config {
     x25_params: X25Params,
-    x3_profiles: HashMap<&'a str, PadParams<UserPadParams>>,
+    x3_profiles: X3Profiles,
     resolver: XotResolver,
-    x3_profile: &'a str,
+    x3_profile: String,
 }
 
-fn load_config(args: &Args) -> Config {
+fn load_config(args: &Args) -> Result<Config, String> {
     let addr = match args.local_addr {
         Some(ref local_addr) => local_addr.clone(),
         None => X121Addr::null(),
@@ -104,50 +214,109 @@ fn load_config(args: &Args) -> Config {
         send_window_size: 2,
         recv_packet_size: 128,
         recv_window_size: 2,
+        max_recv_sequence_len: Some(64 * 1024),
         t21: Duration::from_secs(5),
         t22: Duration::from_secs(5),
         t23: Duration::from_secs(5),
+        addr_format: if args.toa_npi {
+            X25AddrFormat::ToaNpi
+        } else {
+            X25AddrFormat::NonToaNpi
+        },
+        idle_probe: args.idle_probe.map(Duration::from_secs),
+        role: args.x25_role,
+        channels: 1..=1024,
     };
 
-    // TODO...
-    let mut x3_profiles = HashMap::new();
-
-    x3_profiles.insert(
-        "default",
-        PadParams {
-            echo: X3Echo::try_from(1).unwrap(),
-            forward: X3Forward::try_from(126).unwrap(),
-            idle: X3Idle::from(0),
-            lf_insert: X3LfInsert::try_from(0).unwrap(),
-            editing: X3Editing::try_from(0).unwrap(),
-            delegate: Some(UserPadParams {
-                char_delete: X3CharDelete::try_from(127).unwrap(),
-                line_delete: X3LineDelete::try_from(/* Ctrl+X */ 24).unwrap(),
-                line_display: X3LineDisplay::try_from(/* Ctrl+R */ 18).unwrap(),
-            }),
-        },
-    );
+    let mut x3_profiles = standard_profiles();
+
+    if let Some(ref path) = args.config_file {
+        let s = fs::read_to_string(path)
+            .map_err(|err| format!("unable to read {}: {err}", path.display()))?;
+
+        load_profiles(&mut x3_profiles, &s).map_err(|err| format!("{}: {err}", path.display()))?;
+    }
 
     let mut resolver = XotResolver::new();
 
     if let Some(ref xot_gateway) = args.xot_gateway {
-        let _ = resolver.add(".*", xot_gateway);
+        let _ = resolver.add("*", xot_gateway);
     } else {
         #[cfg(feature = "x25_org")]
-        let _ = resolver.add("^(...)(...)", "\\2.\\1.x25.org");
+        let _ = resolver.add("(XXX)(XXX)*", "\\2.\\1.x25.org");
     }
 
-    // TODO...
-    let x3_profile = args.x3_profile.as_str();
+    resolver.set_cache(Some(XotGatewayCache::new(
+        Duration::from_secs(args.resolver_ttl),
+        Duration::from_secs(args.resolver_negative_ttl),
+    )));
+
+    let x3_profile = args.x3_profile.to_lowercase();
 
-    if !x3_profiles.contains_key(x3_profile) {
-        panic!("uuuh that X.3 profile does not exist!");
+    if !x3_profiles.contains_key(&x3_profile) {
+        return Err(format!("unknown X.3 profile: {}", args.x3_profile));
     }
 
-    Config {
+    Ok(Config {
         x25_params,
         x3_profiles,
         resolver,
         x3_profile,
+    })
+}
+
+fn init_tracing(args: &Args) -> io::Result<()> {
+    // Logging to the terminal is only enabled if requested, as it is interleaved
+    // with the PAD output.
+    if args.verbose == 0 && args.log_file.is_none() {
+        return Ok(());
+    }
+
+    let level = match args.verbose {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_thread_names(true);
+
+    if let Some(ref path) = args.log_file {
+        let file = File::create(path)?;
+
+        builder
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+            .init();
+    } else {
+        builder.with_writer(|| RawModeStderr).init();
+    }
+
+    Ok(())
+}
+
+/// Standard error writer for use while the terminal is in raw mode, where a
+/// line feed does not imply a carriage return.
+struct RawModeStderr;
+
+impl Write for RawModeStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut stderr = io::stderr().lock();
+
+        for (index, line) in buf.split(|&b| b == b'\n').enumerate() {
+            if index > 0 {
+                stderr.write_all(b"\r\n")?;
+            }
+
+            stderr.write_all(line)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
     }
 }
@@ -1,13 +1,16 @@
 use bytes::{BufMut, Bytes, BytesMut};
 use chrono::{DateTime, Local};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use libxotpad::clock::SystemClock;
 use libxotpad::pad::{Pad, PadParams};
+use libxotpad::x121::X121Pattern;
 use libxotpad::x25::packet::X25CallRequest;
-use libxotpad::x25::{Svc, Vc, X25Params};
+use libxotpad::x25::{Svc, Vc, VcEvent, X25Params};
 use libxotpad::x29::X29CallUserData;
-use libxotpad::x3::{X3ParamError, X3Params};
-use libxotpad::xot::{self, XotLink, XotResolver};
-use std::collections::HashMap;
+use libxotpad::x3::{X3EditingSignals, X3ParamError, X3Params};
+use libxotpad::xot::{
+    self, XotAcl, XotCapture, XotConnectionLimiter, XotKeepalive, XotLink, XotResolver,
+};
 use std::io::{self, BufReader, Read, Write};
 use std::net::TcpListener;
 use std::str::{self, FromStr};
@@ -19,7 +22,29 @@ use tracing_mutex::stdsync::{Mutex, RwLock};
 
 use crate::util::is_char_delete;
 use crate::x28::{X28Addr, X28Command, X28Selection, X28Signal};
-use crate::x3::UserPadParams;
+use crate::x3::{UserPadParams, X3Profiles};
+
+/// Listener for incoming XOT connections.
+pub struct Listener {
+    pub tcp_listener: TcpListener,
+    pub called: X121Pattern,
+    pub acl: XotAcl,
+    pub limiter: Arc<XotConnectionLimiter>,
+}
+
+/// Options applied to each XOT link.
+#[derive(Clone, Default)]
+pub struct LinkOptions {
+    pub capture: Option<XotCapture>,
+    pub keepalive: Option<XotKeepalive>,
+}
+
+impl LinkOptions {
+    fn apply(&self, xot_link: &mut XotLink) -> io::Result<()> {
+        xot_link.set_capture(self.capture.clone())?;
+        xot_link.set_keepalive(self.keepalive)
+    }
+}
 
 #[derive(Copy, Clone, PartialEq)]
 enum PadLocalState {
@@ -31,14 +56,16 @@ enum PadInput {
     Call(X25CallRequest),
     Local(io::Result<Option<u8>>),
     Remote(io::Result<Option<Bytes>>),
+    Reset(u8, u8),
 }
 
 pub fn run(
     x25_params: &X25Params,
-    x3_profiles: &HashMap<&str, PadParams<UserPadParams>>,
+    x3_profiles: &X3Profiles,
     resolver: &XotResolver,
     x3_profile: &str,
-    tcp_listener: Option<TcpListener>,
+    listener: Option<Listener>,
+    link_options: LinkOptions,
     initial_selection: &Option<X28Selection>,
 ) -> io::Result<()> {
     let (tx, rx) = channel();
@@ -76,19 +103,21 @@ pub fn run(
     let mut local_state = PadLocalState::Command;
     let mut is_one_shot = false;
 
-    let x3_params = Arc::new(RwLock::new(
-        x3_profiles
-            .get(x3_profile)
-            .expect("unknown X.3 profile")
-            .clone(),
-    ));
+    let Some(x3_params) = x3_profiles.get(x3_profile) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown X.3 profile: {x3_profile}"),
+        ));
+    };
+
+    let x3_params = Arc::new(RwLock::new(x3_params.clone()));
 
     let current_call = Arc::new(Mutex::new(Option::<(Pad<UserPadParams>, X25Params)>::None));
 
     if let Some(selection) = initial_selection {
         let x3_params = Arc::clone(&x3_params);
 
-        let call = call(selection, x3_params, x25_params, resolver)?;
+        let call = call(selection, x3_params, x25_params, resolver, &link_options)?;
 
         current_call.lock().unwrap().replace(call);
 
@@ -104,65 +133,119 @@ pub fn run(
         }
     }
 
-    if let Some(tcp_listener) = tcp_listener {
+    if let Some(listener) = listener {
         let x25_params = x25_params.clone();
         let x3_params = Arc::clone(&x3_params);
         let current_call = Arc::clone(&current_call);
+        let link_options = link_options.clone();
         let tx = tx.clone();
 
+        let Listener {
+            tcp_listener,
+            called,
+            acl,
+            limiter,
+        } = listener;
+
+        let called = Arc::new(called);
+        let acl = Arc::new(acl);
+
         thread::Builder::new()
             .name("user_pad_listener".to_string())
             .spawn(move || {
                 for tcp_stream in tcp_listener.incoming() {
-                    if tcp_stream.is_err() {
+                    let Ok(tcp_stream) = tcp_stream else {
                         continue;
-                    }
-
-                    let xot_link = XotLink::new(tcp_stream.unwrap());
+                    };
 
-                    let incoming_call = Svc::listen_timeout(
-                        xot_link,
-                        1, /* this "channel" needs to be removed! */
-                        &x25_params,
-                        Duration::from_secs(200),
-                    );
+                    let Ok(source) = tcp_stream.peer_addr().map(|a| a.ip()) else {
+                        continue;
+                    };
 
-                    if incoming_call.is_err() {
+                    // Dropping the stream will close the connection.
+                    if !acl.is_source_allowed(source) {
                         continue;
                     }
 
-                    let incoming_call = incoming_call.unwrap();
+                    let Some(pending) = limiter.admit(source) else {
+                        continue;
+                    };
 
-                    let mut current_call = current_call.lock().unwrap();
+                    thread::Builder::new()
+                        .name("user_pad_incoming".to_string())
+                        .spawn({
+                            let x25_params = x25_params.clone();
+                            let x3_params = Arc::clone(&x3_params);
+                            let current_call = Arc::clone(&current_call);
+                            let called = Arc::clone(&called);
+                            let acl = Arc::clone(&acl);
+                            let link_options = link_options.clone();
+                            let tx = tx.clone();
+
+                            move || {
+                                let mut xot_link = XotLink::new(tcp_stream);
+
+                                if link_options.apply(&mut xot_link).is_err() {
+                                    return;
+                                }
 
-                    if current_call.is_some() {
-                        let _ = incoming_call.clear(1, 0); // Number busy
-                        continue;
-                    }
+                                let incoming_call = Svc::listen_timeout(
+                                    xot_link,
+                                    1, /* this "channel" needs to be removed! */
+                                    &x25_params,
+                                    Arc::new(SystemClock),
+                                    Duration::from_secs(200),
+                                );
 
-                    let call_request = incoming_call.request().clone();
+                                // The connection is no longer pending, whether or not
+                                // a call was received.
+                                drop(pending);
 
-                    if let Some((cause_code, diagnostic_code)) =
-                        should_accept_call(&call_request, &x25_params)
-                    {
-                        let _ = incoming_call.clear(cause_code, diagnostic_code);
-                        continue;
-                    }
+                                let Ok(incoming_call) = incoming_call else {
+                                    return;
+                                };
 
-                    let svc = incoming_call.accept().unwrap();
+                                let call_request = incoming_call.request().clone();
 
-                    let x25_params = svc.params();
+                                if !acl.is_call_allowed(
+                                    source,
+                                    &call_request.called_addr,
+                                    &call_request.calling_addr,
+                                ) {
+                                    eprint!("\r\nwarning: incoming call denied by access list\r\n");
+                                    let _ = incoming_call.clear(11, 0); // Access barred
+                                    return;
+                                }
 
-                    // TODO: should we "reset" the X.3 parameters here, for a new
-                    // call?
+                                let mut current_call = current_call.lock().unwrap();
 
-                    let pad = Pad::new(svc, Arc::clone(&x3_params), true);
+                                if current_call.is_some() {
+                                    let _ = incoming_call.clear(1, 0); // Number busy
+                                    return;
+                                }
 
-                    current_call.replace((pad, x25_params));
+                                if let Some((cause_code, diagnostic_code)) =
+                                    should_accept_call(&call_request, &called)
+                                {
+                                    let _ = incoming_call.clear(cause_code, diagnostic_code);
+                                    return;
+                                }
 
-                    if tx.send(PadInput::Call(call_request)).is_err() {
-                        break;
-                    }
+                                let svc = incoming_call.accept().unwrap();
+
+                                let x25_params = svc.params();
+
+                                // TODO: should we "reset" the X.3 parameters here, for a new
+                                // call?
+
+                                let pad = Pad::new(svc, Arc::clone(&x3_params), true);
+
+                                current_call.replace((pad, x25_params));
+
+                                let _ = tx.send(PadInput::Call(call_request));
+                            }
+                        })
+                        .expect("failed to spawn thread");
                 }
             })
             .expect("failed to spawn thread");
@@ -172,7 +255,7 @@ pub fn run(
     let mut line_buf = BytesMut::with_capacity(128);
 
     if local_state == PadLocalState::Command {
-        print_prompt();
+        print_prompt(&x3_params);
 
         io::stdout().flush()?;
     }
@@ -186,7 +269,7 @@ pub fn run(
 
         match input {
             PadInput::Call(call_request) => {
-                print_signal(X28Signal::Connected(Some(call_request)), true);
+                print_signal(&x3_params, X28Signal::Connected(Some(call_request)), true);
 
                 local_state = PadLocalState::Data;
 
@@ -197,6 +280,13 @@ pub fn run(
             PadInput::Remote(Ok(Some(buf))) => {
                 io::stdout().write_all(&buf)?;
             }
+            PadInput::Reset(cause_code, diagnostic_code) => {
+                print_signal(
+                    &x3_params,
+                    X28Signal::Reset(cause_code, diagnostic_code),
+                    true,
+                );
+            }
             PadInput::Remote(Ok(None)) => {
                 // If there is a current call then the clear was requested by the remote party,
                 // it may have been as a result of an invite clear X.29 PAD message from us.
@@ -217,12 +307,12 @@ pub fn run(
                 let mut new_line = true;
 
                 if let Some(signal) = signal {
-                    print_signal(signal, true);
+                    print_signal(&x3_params, signal, true);
 
                     new_line = false;
                 }
 
-                ensure_command(&mut local_state, new_line);
+                ensure_command(&x3_params, &mut local_state, new_line);
             }
             PadInput::Remote(Err(err)) => {
                 println!("remote error: {err:?}");
@@ -233,7 +323,7 @@ pub fn run(
                     break;
                 }
 
-                ensure_command(&mut local_state, true);
+                ensure_command(&x3_params, &mut local_state, true);
             }
             PadInput::Local(Ok(None) | Err(_)) => {
                 if let Some((mut pad, _)) = current_call.take() {
@@ -254,17 +344,20 @@ pub fn run(
                     command_buf.clear();
 
                     print!("\r\n");
-                    print_prompt();
+                    print_prompt(&x3_params);
                 }
-                (PadLocalState::Command, /* Ctrl+P */ 0x10) => {
-                    if command_buf.is_empty() && current_call.is_some() {
-                        let (pad, _) = current_call.as_mut().unwrap();
+                // Entering the recall character again, immediately after recall, sends it.
+                (PadLocalState::Command, byte)
+                    if is_recall(&x3_params, byte)
+                        && command_buf.is_empty()
+                        && current_call.is_some() =>
+                {
+                    let (pad, _) = current_call.as_mut().unwrap();
 
-                        pad.write_all(&[byte])?;
+                    pad.write_all(&[byte])?;
 
-                        print!("\r\n");
-                        local_state = PadLocalState::Data;
-                    }
+                    print!("\r\n");
+                    local_state = PadLocalState::Data;
                 }
                 (PadLocalState::Command, byte) => 'input: {
                     let Some(line) = handle_command_input(&mut command_buf, byte)? else {
@@ -273,6 +366,8 @@ pub fn run(
 
                     let line = line.trim();
 
+                    let mut should_escape = false;
+
                     if !line.is_empty() {
                         if line.to_uppercase() == "EXIT" {
                             if let Some((x29_pad, _)) = current_call.take() {
@@ -287,15 +382,26 @@ pub fn run(
                         match command {
                             Ok(X28Command::Selection(ref selection)) => {
                                 if current_call.is_some() {
-                                    print_signal(X28Signal::Error, false); // Connected
+                                    // Connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
                                 } else {
-                                    let x3_params = Arc::clone(&x3_params);
-
-                                    match call(selection, x3_params, x25_params, resolver) {
+                                    print_calling(selection);
+
+                                    match call(
+                                        selection,
+                                        Arc::clone(&x3_params),
+                                        x25_params,
+                                        resolver,
+                                        &link_options,
+                                    ) {
                                         Ok(call) => {
                                             current_call.replace(call);
 
-                                            print_signal(X28Signal::Connected(None), false);
+                                            print_signal(
+                                                &x3_params,
+                                                X28Signal::Connected(None),
+                                                false,
+                                            );
 
                                             local_state = PadLocalState::Data;
 
@@ -311,9 +417,10 @@ pub fn run(
                                 if let Some((pad, _)) = current_call.take() {
                                     pad.into_svc().clear(0, 0)?;
 
-                                    print_signal(X28Signal::Cleared(None), false);
+                                    print_signal(&x3_params, X28Signal::Cleared(None), false);
                                 } else {
-                                    print_signal(X28Signal::Error, false); // Not connected
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
                                 }
 
                                 if is_one_shot {
@@ -324,6 +431,7 @@ pub fn run(
                                 let response = read_params(&x3_params.read().unwrap(), request);
 
                                 print_signal(
+                                    &x3_params,
                                     X28Signal::LocalParams(normalize_read_params_response(
                                         &response,
                                     )),
@@ -338,19 +446,42 @@ pub fn run(
                                     response.into_iter().filter(|(_, r)| r.is_ok()).collect();
 
                                 if !invalid.is_empty() {
-                                    print_signal(X28Signal::LocalParams(invalid), false);
+                                    print_signal(
+                                        &x3_params,
+                                        X28Signal::LocalParams(invalid),
+                                        false,
+                                    );
+                                }
+
+                                if let Some((pad, _)) = current_call.as_ref() {
+                                    pad.params_changed();
                                 }
                             }
                             Ok(X28Command::SetRead(ref request)) => {
                                 let response = set_params(&mut x3_params.write().unwrap(), request);
 
-                                print_signal(X28Signal::LocalParams(response), false);
+                                print_signal(&x3_params, X28Signal::LocalParams(response), false);
+
+                                if let Some((pad, _)) = current_call.as_ref() {
+                                    pad.params_changed();
+                                }
+                            }
+                            Ok(X28Command::Profile(ref name)) => {
+                                match x3_profiles.get(&name.to_lowercase()) {
+                                    Some(profile) => *x3_params.write().unwrap() = profile.clone(),
+                                    None => print_signal(&x3_params, X28Signal::Error, false), // Unknown profile
+                                }
+
+                                if let Some((pad, _)) = current_call.as_ref() {
+                                    pad.params_changed();
+                                }
                             }
                             Ok(X28Command::RemoteRead(ref request)) => {
                                 if let Some((pad, _)) = current_call.as_ref() {
                                     match pad.get_remote_params(request) {
                                         Ok(response) => {
                                             print_signal(
+                                                &x3_params,
                                                 X28Signal::RemoteParams(
                                                     normalize_read_params_response(&response),
                                                 ),
@@ -363,14 +494,19 @@ pub fn run(
                                         Err(err) => return Err(err),
                                     };
                                 } else {
-                                    print_signal(X28Signal::Error, false); // Not connected
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
                                 }
                             }
                             Ok(X28Command::RemoteSetRead(ref request)) => {
                                 if let Some((pad, _)) = current_call.as_ref() {
                                     match pad.set_remote_params(request) {
                                         Ok(response) => {
-                                            print_signal(X28Signal::RemoteParams(response), false);
+                                            print_signal(
+                                                &x3_params,
+                                                X28Signal::RemoteParams(response),
+                                                false,
+                                            );
                                         }
                                         Err(err) if err.kind() == io::ErrorKind::TimedOut => {
                                             eprintln!(
@@ -380,7 +516,8 @@ pub fn run(
                                         Err(err) => return Err(err),
                                     };
                                 } else {
-                                    print_signal(X28Signal::Error, false); // Not connected
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
                                 }
                             }
                             Ok(X28Command::Status) => {
@@ -390,7 +527,21 @@ pub fn run(
                                     X28Signal::Free
                                 };
 
-                                print_signal(signal, false);
+                                print_signal(&x3_params, signal, false);
+                            }
+                            Ok(X28Command::Statistics) => {
+                                if let Some((pad, _)) = current_call.as_ref() {
+                                    let (stats, link_stats) = pad.stats();
+
+                                    print_signal(
+                                        &x3_params,
+                                        X28Signal::Statistics(stats, link_stats),
+                                        false,
+                                    );
+                                } else {
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
+                                }
                             }
                             Ok(X28Command::InviteClear) => {
                                 if let Some((pad, _)) = current_call.as_ref() {
@@ -399,22 +550,47 @@ pub fn run(
                                     // TODO: Implement timeout, if clear request not received from
                                     // remote PAD send a clear request.
                                 } else {
-                                    print_signal(X28Signal::Error, false); // Not connected
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
+                                }
+                            }
+                            Ok(X28Command::FlushCache) => {
+                                if let Some(cache) = resolver.cache() {
+                                    let stats = cache.stats();
+
+                                    cache.flush();
+
+                                    print_signal(&x3_params, X28Signal::CacheFlushed(stats), false);
+                                } else {
+                                    // No cache
+                                    print_signal(&x3_params, X28Signal::Error, false);
+                                }
+                            }
+                            Ok(X28Command::Break) => {
+                                if let Some((pad, _)) = current_call.as_ref() {
+                                    match pad.send_break() {
+                                        Ok(escape) => should_escape = escape,
+                                        // The call may have been cleared meanwhile.
+                                        Err(_) => print_signal(&x3_params, X28Signal::Error, false),
+                                    }
+                                } else {
+                                    // Not connected
+                                    print_signal(&x3_params, X28Signal::Error, false);
                                 }
                             }
                             Ok(X28Command::Help(subject)) => print_help(&subject),
-                            Err(_) => print_signal(X28Signal::Error, false),
+                            Err(_) => print_signal(&x3_params, X28Signal::Error, false),
                         }
                     }
 
-                    if current_call.is_some() {
+                    if current_call.is_some() && !should_escape {
                         local_state = PadLocalState::Data;
                     } else {
-                        print_prompt();
+                        print_prompt(&x3_params);
                     }
                 }
-                (PadLocalState::Data, /* Ctrl+P */ 0x10) => {
-                    ensure_command(&mut local_state, true);
+                (PadLocalState::Data, byte) if is_recall(&x3_params, byte) => {
+                    ensure_command(&x3_params, &mut local_state, true);
                 }
                 (PadLocalState::Data, byte) => 'input: {
                     let (pad, _) = current_call.as_mut().unwrap();
@@ -424,25 +600,30 @@ pub fn run(
 
                     let editing: bool = pad_params.editing.into();
 
-                    if editing {
+                    // Flow control characters are handled by the PAD immediately, they
+                    // must not be held in the editing buffer.
+                    let is_flow_control = pad_params.flow_control.into()
+                        && [/* X-ON */ 0x11, /* X-OFF */ 0x13].contains(&byte);
+
+                    if editing && !is_flow_control {
                         if params.char_delete.is_match(byte) {
-                            handle_char_delete(&mut line_buf)?;
+                            handle_char_delete(&mut line_buf, pad_params.editing_signals)?;
                             break 'input;
                         } else if params.line_delete.is_match(byte) {
-                            handle_line_delete(&mut line_buf)?;
+                            handle_line_delete(&mut line_buf, &pad_params)?;
                             break 'input;
                         } else if params.line_display.is_match(byte) {
-                            handle_line_display(&line_buf)?;
+                            handle_line_display(&line_buf, &pad_params)?;
                             break 'input;
                         }
 
                         line_buf.put_u8(byte);
 
-                        if pad_params.echo.into() {
-                            io::stdout().write_all(&[byte])?;
+                        if pad_params.echo.into() && !pad_params.echo_mask.is_match(byte) {
+                            write_padded(&[byte], &pad_params)?;
 
                             if pad_params.lf_insert.after_echo(byte) {
-                                io::stdout().write_all(&[/* LF */ 0x0a])?;
+                                write_padded(&[/* LF */ 0x0a], &pad_params)?;
                             }
                         }
 
@@ -473,6 +654,33 @@ pub fn run(
 }
 
 fn spawn_remote_thread(pad: &Pad<UserPadParams>, channel: Sender<PadInput>) -> JoinHandle<()> {
+    let events = pad.subscribe();
+
+    thread::Builder::new()
+        .name("user_pad_events".to_string())
+        .spawn({
+            let channel = channel.clone();
+
+            // The subscription ends when the circuit is gone.
+            move || {
+                for event in events {
+                    let input = match event {
+                        VcEvent::Reset {
+                            cause_code,
+                            diagnostic_code,
+                        } => PadInput::Reset(cause_code, diagnostic_code),
+                        VcEvent::Cleared { .. } | VcEvent::OutOfOrder(_) => break,
+                        _ => continue,
+                    };
+
+                    if channel.send(input).is_err() {
+                        break;
+                    }
+                }
+            }
+        })
+        .expect("failed to spawn thread");
+
     let mut pad = pad.clone();
 
     thread::Builder::new()
@@ -508,6 +716,7 @@ fn call(
     x3_params: Arc<RwLock<PadParams<UserPadParams>>>,
     x25_params: &X25Params,
     resolver: &XotResolver,
+    link_options: &LinkOptions,
 ) -> io::Result<(Pad<UserPadParams>, X25Params)> {
     assert!(!selection.addrs.is_empty());
 
@@ -527,18 +736,32 @@ fn call(
 
     let call_data = selection.call_user_data.as_bytes(); // TODO: as_ascii_bytes()
 
-    let xot_link = xot::connect(addr, resolver)?;
+    let mut xot_link = xot::connect(addr, resolver)?;
 
-    let pad = Pad::call(xot_link, 1, addr, call_data, x25_params, x3_params, true)?;
+    link_options.apply(&mut xot_link)?;
+
+    // There is only ever a single call on each XOT link.
+    let channel = x25_params
+        .allocate_channel(|_| false)
+        .ok_or_else(|| io::Error::other("no logical channels available"))?;
+
+    let pad = Pad::call(
+        xot_link,
+        channel,
+        addr,
+        call_data,
+        x25_params,
+        Arc::new(SystemClock),
+        x3_params,
+        true,
+    )?;
 
     Ok((pad, x25_params.clone()))
 }
 
-fn should_accept_call(call_request: &X25CallRequest, params: &X25Params) -> Option<(u8, u8)> {
-    let local_addr = &params.addr;
-
-    if !local_addr.is_null() && !call_request.called_addr.starts_with(local_addr) {
-        eprint!("\r\nwarning: incoming call address does not match local address prefix\r\n");
+fn should_accept_call(call_request: &X25CallRequest, called: &X121Pattern) -> Option<(u8, u8)> {
+    if !called.is_match(&call_request.called_addr) {
+        eprint!("\r\nwarning: incoming call address does not match called address pattern\r\n");
         return Some((13, 67)); // Not obtainable - invalid called DTE address
     }
 
@@ -589,56 +812,108 @@ fn normalize_read_params_response(
         .collect()
 }
 
-fn handle_char_delete(buf: &mut BytesMut) -> io::Result<()> {
+fn write_padded(buf: &[u8], params: &PadParams<UserPadParams>) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    for &byte in buf {
+        stdout.write_all(&[byte])?;
+        stdout.write_all(&vec![/* NUL */ 0x00; params.padding(byte)])?;
+    }
+
+    Ok(())
+}
+
+fn is_recall(x3_params: &RwLock<PadParams<UserPadParams>>, byte: u8) -> bool {
+    x3_params.read().unwrap().recall.is_match(byte)
+}
+
+fn handle_char_delete(buf: &mut BytesMut, signals: X3EditingSignals) -> io::Result<()> {
     if buf.is_empty() {
         return Ok(());
     }
 
     buf.truncate(buf.len() - 1);
 
-    io::stdout().write_all(&[0x08, 0x20, 0x08])
+    match *signals {
+        0 => Ok(()),
+        // Printing terminals can not erase, so a backslash is shown instead.
+        1 => io::stdout().write_all(b"\\"),
+        2 => io::stdout().write_all(&[0x08, 0x20, 0x08]),
+        signal => io::stdout().write_all(&[signal]),
+    }
 }
 
-fn handle_line_delete(buf: &mut BytesMut) -> io::Result<()> {
+fn handle_line_delete(buf: &mut BytesMut, params: &PadParams<UserPadParams>) -> io::Result<()> {
     if buf.is_empty() {
         return Ok(());
     }
 
     buf.clear();
 
-    // This is the indication the line delete function has completed for printing terminals. Video
-    // terminals should use a rpetition of the BS SP BS sequence to clear the line but it appears
-    // the Cisco x28 command just displays the printing terminal indication.
-    io::stdout().write_all(b"XXX\r\n")
+    match *params.editing_signals {
+        0 => Ok(()),
+        // This is the indication the line delete function has completed for printing terminals.
+        // Video terminals should use a repetition of the BS SP BS sequence to clear the line but
+        // it appears the Cisco x28 command just displays the printing terminal indication.
+        _ => write_padded(b"XXX\r\n", params),
+    }
 }
 
-fn handle_line_display(buf: &BytesMut) -> io::Result<()> {
-    io::stdout().write_all(b"\r\n")?;
-    io::stdout().write_all(buf)
+fn handle_line_display(buf: &BytesMut, params: &PadParams<UserPadParams>) -> io::Result<()> {
+    write_padded(b"\r\n", params)?;
+    write_padded(buf, params)
 }
 
-fn print_prompt() {
+fn print_prompt(x3_params: &RwLock<PadParams<UserPadParams>>) {
+    if !x3_params.read().unwrap().service_signals.has_prompt() {
+        return;
+    }
+
     print!("*");
 }
 
-fn print_signal(signal: X28Signal, new_line: bool) {
-    if new_line {
-        print!("\r\n");
+fn print_calling(selection: &X28Selection) {
+    let Some(X28Addr::Full(addr)) = selection.addrs.first() else {
+        return;
+    };
+
+    match addr.network() {
+        Some(network) => print!("Calling {addr} ({network})\r\n"),
+        None => print!("Calling {addr}\r\n"),
     }
+}
+
+fn print_signal(x3_params: &RwLock<PadParams<UserPadParams>>, signal: X28Signal, new_line: bool) {
+    let x3_params = x3_params.read().unwrap();
 
-    print!("{signal}\r\n");
+    if !x3_params.service_signals.has_signals() {
+        return;
+    }
+
+    let signal = if new_line {
+        format!("\r\n{signal}\r\n")
+    } else {
+        format!("{signal}\r\n")
+    };
+
+    // There is nothing more to be done if the terminal can not be written to.
+    let _ = write_padded(signal.as_bytes(), &x3_params);
 }
 
-fn ensure_command(state: &mut PadLocalState, new_line: bool) {
+fn ensure_command(
+    x3_params: &RwLock<PadParams<UserPadParams>>,
+    state: &mut PadLocalState,
+    new_line: bool,
+) {
     if *state == PadLocalState::Command {
         return;
     }
 
     if new_line {
-        print!("\r\n");
+        let _ = write_padded(b"\r\n", &x3_params.read().unwrap());
     }
 
-    print_prompt();
+    print_prompt(x3_params);
 
     *state = PadLocalState::Command;
 }
@@ -654,8 +929,9 @@ fn handle_command_input(buf: &mut BytesMut, byte: u8) -> io::Result<Option<Strin
         return Ok(Some(line.to_string()));
     }
 
+    // The command line is always edited as a display terminal.
     if is_char_delete(byte) {
-        handle_char_delete(buf)?;
+        handle_char_delete(buf, X3EditingSignals::try_from(2).unwrap())?;
 
         return Ok(None);
     }
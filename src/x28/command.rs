@@ -10,10 +10,14 @@ pub enum X28Command {
     Read(Vec<u8>),
     Set(Vec<(u8, u8)>),
     SetRead(Vec<(u8, u8)>),
+    Profile(String),
     RemoteRead(Vec<u8>),
     RemoteSetRead(Vec<(u8, u8)>),
     Status,
+    Statistics,
     InviteClear,
+    FlushCache,
+    Break,
     Help(String),
 }
 
@@ -61,6 +65,13 @@ impl FromStr for X28Command {
 
                 Ok(X28Command::SetRead(params))
             }
+            "PROF" | "PROFILE" => {
+                if rest.trim().is_empty() {
+                    return Err("profile argument required".into());
+                }
+
+                Ok(X28Command::Profile(rest.trim().to_string()))
+            }
             "RPAR?" | "RREAD" => {
                 let params = parse_read_params(rest)?;
 
@@ -76,7 +87,10 @@ impl FromStr for X28Command {
                 Ok(X28Command::RemoteSetRead(params))
             }
             "STAT" | "STATUS" => Ok(X28Command::Status),
+            "STATS" | "STATISTICS" => Ok(X28Command::Statistics),
             "ICLR" | "ICLEAR" => Ok(X28Command::InviteClear),
+            "FLUSH" => Ok(X28Command::FlushCache),
+            "BRK" | "BREAK" => Ok(X28Command::Break),
             "HELP" => Ok(X28Command::Help(rest.to_string())),
             _ => match X28Selection::from_str(&command) {
                 Ok(selection) => Ok(X28Command::Selection(selection)),
@@ -243,6 +257,23 @@ mod tests {
         assert!(X28Command::from_str("set? ,").is_err());
     }
 
+    #[test]
+    fn from_str_profile() {
+        assert_eq!(
+            X28Command::from_str("prof 90"),
+            Ok(X28Command::Profile("90".to_string()))
+        );
+        assert_eq!(
+            X28Command::from_str("profile transparent"),
+            Ok(X28Command::Profile("transparent".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_str_profile_invalid() {
+        assert!(X28Command::from_str("prof").is_err());
+    }
+
     #[test]
     fn from_str_remote_read() {
         assert_eq!(
@@ -301,12 +332,32 @@ mod tests {
         assert_eq!(X28Command::from_str("status"), Ok(X28Command::Status));
     }
 
+    #[test]
+    fn from_str_statistics() {
+        assert_eq!(X28Command::from_str("stats"), Ok(X28Command::Statistics));
+        assert_eq!(
+            X28Command::from_str("statistics"),
+            Ok(X28Command::Statistics)
+        );
+    }
+
     #[test]
     fn from_str_invite_clear() {
         assert_eq!(X28Command::from_str("iclr"), Ok(X28Command::InviteClear));
         assert_eq!(X28Command::from_str("iclear"), Ok(X28Command::InviteClear));
     }
 
+    #[test]
+    fn from_str_flush_cache() {
+        assert_eq!(X28Command::from_str("flush"), Ok(X28Command::FlushCache));
+    }
+
+    #[test]
+    fn from_str_break() {
+        assert_eq!(X28Command::from_str("brk"), Ok(X28Command::Break));
+        assert_eq!(X28Command::from_str("break"), Ok(X28Command::Break));
+    }
+
     #[test]
     fn from_str_help() {
         assert_eq!(
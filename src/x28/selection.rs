@@ -56,7 +56,7 @@ fn parse_facilities(s: &str) -> Result<(Vec<(char, &str)>, &str), String> {
     let mut rest = s;
 
     while !rest.is_empty() {
-        let index = rest.find(|c| c == ',' || c == '-').unwrap_or(rest.len());
+        let index = rest.find([',', '-']).unwrap_or(rest.len());
 
         let facility;
 
@@ -1,15 +1,20 @@
 use libxotpad::x25::packet::X25CallRequest;
+use libxotpad::x25::SvcStats;
 use libxotpad::x3::X3ParamError;
+use libxotpad::xot::{XotGatewayCacheStats, XotLinkStats};
 use std::fmt::{self, Write};
 
 /// X.28 _service_ signal.
 pub enum X28Signal {
     Connected(Option<X25CallRequest>),
     Cleared(Option<(u8, u8)>),
+    Reset(u8, u8),
     Free,
     Engaged,
     LocalParams(Vec<(u8, Result<u8, X3ParamError>)>),
     RemoteParams(Vec<(u8, Result<u8, X3ParamError>)>),
+    CacheFlushed(XotGatewayCacheStats),
+    Statistics(SvcStats, XotLinkStats),
     Error,
 }
 
@@ -24,6 +29,10 @@ impl fmt::Display for X28Signal {
                 write!(fmt, "CLR {cause} C:{cause_code} D:{diagnostic_code}")
             }
             X28Signal::Cleared(None) => write!(fmt, "CLR CONF"),
+            X28Signal::Reset(cause_code, diagnostic_code) => match reset_cause(*cause_code) {
+                Some(cause) => write!(fmt, "RESET {cause} C:{cause_code} D:{diagnostic_code}"),
+                None => write!(fmt, "RESET C:{cause_code} D:{diagnostic_code}"),
+            },
             X28Signal::Free => write!(fmt, "FREE"),
             X28Signal::Engaged => write!(fmt, "ENGAGED"),
             X28Signal::LocalParams(params) => {
@@ -36,6 +45,67 @@ impl fmt::Display for X28Signal {
 
                 write!(fmt, "RPAR {params}")
             }
+            X28Signal::CacheFlushed(stats) => {
+                let XotGatewayCacheStats {
+                    hits,
+                    negative_hits,
+                    misses,
+                    entries,
+                    ..
+                } = stats;
+
+                write!(
+                    fmt,
+                    "FLUSHED {entries} HITS:{hits} NEG:{negative_hits} MISSES:{misses}"
+                )
+            }
+            X28Signal::Statistics(stats, link_stats) => {
+                let SvcStats {
+                    packets_sent,
+                    packets_received,
+                    bytes_sent,
+                    bytes_received,
+                    data_packets_sent,
+                    data_packets_received,
+                    packets_retransmitted,
+                    rr_sent,
+                    rr_received,
+                    rnr_sent,
+                    rnr_received,
+                    resets_sent,
+                    resets_received,
+                    blocked_time,
+                    call_setup_time,
+                } = stats;
+
+                write!(
+                    fmt,
+                    "STATS PKT S:{packets_sent} R:{packets_received}, \
+                     BYTE S:{bytes_sent} R:{bytes_received}, \
+                     DATA S:{data_packets_sent} R:{data_packets_received}, \
+                     RR S:{rr_sent} R:{rr_received}, RNR S:{rnr_sent} R:{rnr_received}, \
+                     RESET S:{resets_sent} R:{resets_received}, \
+                     RETX {packets_retransmitted}, BLOCKED {}ms",
+                    blocked_time.as_millis()
+                )?;
+
+                if let Some(call_setup_time) = call_setup_time {
+                    write!(fmt, ", SETUP {}ms", call_setup_time.as_millis())?;
+                }
+
+                let XotLinkStats {
+                    packets_sent,
+                    packets_received,
+                    bytes_sent,
+                    bytes_received,
+                } = link_stats;
+
+                write!(
+                    fmt,
+                    "\r\nLINK PKT S:{packets_sent} R:{packets_received}, \
+                     BYTE S:{bytes_sent} R:{bytes_received}"
+                )
+            }
             X28Signal::Error => write!(fmt, "ERR"),
         }
     }
@@ -45,6 +115,21 @@ fn clear_cause(_code: u8) -> &'static str {
     "TODO"
 }
 
+fn reset_cause(code: u8) -> Option<&'static str> {
+    // A cause with the high bit set is originated by the remote DTE.
+    if code & 0x80 != 0 {
+        return Some("DTE");
+    }
+
+    match code {
+        0 => Some("DTE"),
+        3 => Some("RPE"),
+        5 => Some("ERR"),
+        7 => Some("NC"),
+        _ => None,
+    }
+}
+
 fn format_params(params: &[(u8, Result<u8, X3ParamError>)]) -> String {
     let mut s = String::new();
 
@@ -66,6 +151,8 @@ fn format_params(params: &[(u8, Result<u8, X3ParamError>)]) -> String {
 mod tests {
     use super::*;
 
+    use std::time::Duration;
+
     #[test]
     fn fmt_cleared_local() {
         let signal = X28Signal::Cleared(None);
@@ -80,6 +167,15 @@ mod tests {
         assert_eq!(signal.to_string(), "CLR TODO C:0 D:0");
     }
 
+    #[test]
+    fn fmt_reset() {
+        assert_eq!(X28Signal::Reset(0, 0).to_string(), "RESET DTE C:0 D:0");
+        assert_eq!(X28Signal::Reset(0x85, 1).to_string(), "RESET DTE C:133 D:1");
+        assert_eq!(X28Signal::Reset(5, 2).to_string(), "RESET ERR C:5 D:2");
+        assert_eq!(X28Signal::Reset(7, 0).to_string(), "RESET NC C:7 D:0");
+        assert_eq!(X28Signal::Reset(29, 0).to_string(), "RESET C:29 D:0");
+    }
+
     #[test]
     fn fmt_local_params() {
         let signal = X28Signal::LocalParams(vec![
@@ -101,4 +197,50 @@ mod tests {
 
         assert_eq!(signal.to_string(), "RPAR 1:1, 2:INV, 3:3");
     }
+
+    #[test]
+    fn fmt_cache_flushed() {
+        let signal = X28Signal::CacheFlushed(XotGatewayCacheStats {
+            hits: 10,
+            negative_hits: 2,
+            misses: 4,
+            failures: 1,
+            entries: 3,
+        });
+
+        assert_eq!(signal.to_string(), "FLUSHED 3 HITS:10 NEG:2 MISSES:4");
+    }
+
+    #[test]
+    fn fmt_statistics() {
+        let stats = SvcStats {
+            packets_sent: 5,
+            packets_received: 6,
+            bytes_sent: 50,
+            bytes_received: 60,
+            data_packets_sent: 2,
+            data_packets_received: 3,
+            rr_sent: 1,
+            rr_received: 2,
+            blocked_time: Duration::from_millis(150),
+            call_setup_time: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let link_stats = XotLinkStats {
+            packets_sent: 5,
+            packets_received: 6,
+            bytes_sent: 70,
+            bytes_received: 84,
+        };
+
+        let signal = X28Signal::Statistics(stats, link_stats);
+
+        assert_eq!(
+            signal.to_string(),
+            "STATS PKT S:5 R:6, BYTE S:50 R:60, DATA S:2 R:3, RR S:1 R:2, RNR S:0 R:0, \
+             RESET S:0 R:0, RETX 0, BLOCKED 150ms, SETUP 20ms\r\n\
+             LINK PKT S:5 R:6, BYTE S:70 R:84"
+        );
+    }
 }
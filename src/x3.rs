@@ -1,5 +1,12 @@
-use libxotpad::x3::X3ParamError;
+use libxotpad::pad::PadParams;
+use libxotpad::x3::{
+    X3AncillaryControl, X3Break, X3CrPadding, X3DiscardOutput, X3Echo, X3EchoMask, X3Editing,
+    X3EditingSignals, X3FlowControl, X3Forward, X3Idle, X3LfInsert, X3LfPadding, X3LineFolding,
+    X3PageWait, X3ParamError, X3Params, X3Parity, X3Recall, X3ServiceSignals, X3Speed,
+};
+use std::collections::HashMap;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use crate::util::is_char_delete;
 
@@ -14,7 +21,7 @@ pub struct UserPadParams {
     pub line_display: X3LineDisplay,
 }
 
-impl libxotpad::x3::X3Params for UserPadParams {
+impl X3Params for UserPadParams {
     fn get(&self, param: u8) -> Option<u8> {
         match param {
             16 => Some(*self.char_delete),
@@ -46,6 +53,179 @@ impl libxotpad::x3::X3Params for UserPadParams {
     }
 }
 
+/// Named X.3 profiles, by lowercase name.
+pub type X3Profiles = HashMap<String, PadParams<UserPadParams>>;
+
+/// Standard CCITT simple profile, from X.28, suitable for interactive use.
+const SIMPLE_PROFILE: [(u8, u8); 20] = [
+    (1, 1),
+    (2, 1),
+    (3, 126),
+    (4, 0),
+    (5, 1),
+    (6, 1),
+    (7, 2),
+    (8, 0),
+    (9, 0),
+    (10, 0),
+    (12, 1),
+    (13, 0),
+    (14, 0),
+    (15, 0),
+    (16, 127),
+    (17, 24),
+    (18, 18),
+    (19, 1),
+    (20, 0),
+    (21, 0),
+];
+
+/// Standard CCITT transparent profile, from X.28, suitable for file transfer.
+const TRANSPARENT_PROFILE: [(u8, u8); 20] = [
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (4, 20),
+    (5, 0),
+    (6, 0),
+    (7, 2),
+    (8, 0),
+    (9, 0),
+    (10, 0),
+    (12, 0),
+    (13, 0),
+    (14, 0),
+    (15, 0),
+    (16, 127),
+    (17, 24),
+    (18, 18),
+    (19, 1),
+    (20, 0),
+    (21, 0),
+];
+
+/// Returns the built-in profiles.
+///
+/// These are the `default` profile, the CCITT `simple` and `transparent`
+/// profiles, and the X.28 profile identifiers `90` and `91` for them.
+pub fn standard_profiles() -> X3Profiles {
+    let default = default_profile();
+
+    let simple = with_params(&default, &SIMPLE_PROFILE).unwrap();
+    let transparent = with_params(&default, &TRANSPARENT_PROFILE).unwrap();
+
+    let mut profiles = HashMap::new();
+
+    profiles.insert("default".to_string(), default);
+    profiles.insert("simple".to_string(), simple.clone());
+    profiles.insert("90".to_string(), simple);
+    profiles.insert("transparent".to_string(), transparent.clone());
+    profiles.insert("91".to_string(), transparent);
+
+    profiles
+}
+
+/// Loads user-defined profiles, adding them to the profiles provided.
+///
+/// Profiles are defined using the Cisco syntax, one per line:
+///
+/// ```text
+/// # Comment
+/// x29 profile NAME PARAM:VALUE [PARAM:VALUE...]
+/// ```
+///
+/// Parameters not specified take the value from the `default` profile.
+pub fn load_profiles(profiles: &mut X3Profiles, s: &str) -> Result<(), String> {
+    for (index, line) in s.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, profile) =
+            parse_profile(profiles, line).map_err(|err| format!("line {}: {err}", index + 1))?;
+
+        profiles.insert(name, profile);
+    }
+
+    Ok(())
+}
+
+fn parse_profile(
+    profiles: &X3Profiles,
+    line: &str,
+) -> Result<(String, PadParams<UserPadParams>), String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let [x29, profile, name, params @ ..] = &words[..] else {
+        return Err("expected x29 profile NAME PARAM:VALUE...".into());
+    };
+
+    if !x29.eq_ignore_ascii_case("x29") || !profile.eq_ignore_ascii_case("profile") {
+        return Err("expected x29 profile NAME PARAM:VALUE...".into());
+    }
+
+    let params: Vec<(u8, u8)> = params
+        .iter()
+        .map(|param| {
+            let value = param
+                .split_once(':')
+                .and_then(|(p, v)| Some((u8::from_str(p).ok()?, u8::from_str(v).ok()?)));
+
+            value.ok_or_else(|| format!("invalid parameter {param}"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let profile = with_params(&profiles["default"], &params)?;
+
+    Ok((name.to_lowercase(), profile))
+}
+
+fn with_params(
+    base: &PadParams<UserPadParams>,
+    params: &[(u8, u8)],
+) -> Result<PadParams<UserPadParams>, String> {
+    let mut profile = base.clone();
+
+    for &(param, value) in params {
+        if profile.set(param, value).is_err() {
+            return Err(format!("invalid parameter {param}:{value}"));
+        }
+    }
+
+    Ok(profile)
+}
+
+fn default_profile() -> PadParams<UserPadParams> {
+    PadParams {
+        recall: X3Recall::try_from(/* Ctrl+P */ 1).unwrap(),
+        echo: X3Echo::try_from(1).unwrap(),
+        forward: X3Forward::try_from(126).unwrap(),
+        idle: X3Idle::from(0),
+        ancillary_control: X3AncillaryControl::try_from(0).unwrap(),
+        service_signals: X3ServiceSignals::try_from(5).unwrap(),
+        on_break: X3Break::try_from(0).unwrap(),
+        discard_output: X3DiscardOutput::try_from(0).unwrap(),
+        cr_padding: X3CrPadding::from(0),
+        line_folding: X3LineFolding::from(0),
+        speed: X3Speed::try_from(/* 9600 bit/s */ 14).unwrap(),
+        flow_control: X3FlowControl::try_from(0).unwrap(),
+        lf_insert: X3LfInsert::try_from(0).unwrap(),
+        lf_padding: X3LfPadding::from(0),
+        editing: X3Editing::try_from(0).unwrap(),
+        editing_signals: X3EditingSignals::try_from(/* BS SP BS */ 2).unwrap(),
+        echo_mask: X3EchoMask::from(0),
+        parity: X3Parity::try_from(0).unwrap(),
+        page_wait: X3PageWait::from(0),
+        delegate: Some(UserPadParams {
+            char_delete: X3CharDelete::try_from(127).unwrap(),
+            line_delete: X3LineDelete::try_from(/* Ctrl+X */ 24).unwrap(),
+            line_display: X3LineDisplay::try_from(/* Ctrl+R */ 18).unwrap(),
+        }),
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct X3CharDelete(u8);
 
@@ -135,3 +315,73 @@ impl X3LineDisplay {
         byte == self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_profiles_simple() {
+        let profiles = standard_profiles();
+
+        let simple = &profiles["simple"];
+
+        assert_eq!(simple.get(1), Some(1));
+        assert_eq!(simple.get(2), Some(1));
+        assert_eq!(simple.get(4), Some(0));
+        assert_eq!(simple.get(19), Some(1));
+
+        assert_eq!(profiles["90"].all(), simple.all());
+    }
+
+    #[test]
+    fn standard_profiles_transparent() {
+        let profiles = standard_profiles();
+
+        let transparent = &profiles["transparent"];
+
+        assert_eq!(transparent.get(1), Some(0));
+        assert_eq!(transparent.get(2), Some(0));
+        assert_eq!(transparent.get(3), Some(0));
+        assert_eq!(transparent.get(4), Some(20));
+
+        assert_eq!(profiles["91"].all(), transparent.all());
+    }
+
+    #[test]
+    fn load_profiles_valid() {
+        let mut profiles = standard_profiles();
+
+        let s = "# Comment\n\nx29 profile Quiet 2:0 6:0\n";
+
+        assert!(load_profiles(&mut profiles, s).is_ok());
+
+        let quiet = &profiles["quiet"];
+
+        assert_eq!(quiet.get(2), Some(0));
+        assert_eq!(quiet.get(6), Some(0));
+        assert_eq!(quiet.get(3), profiles["default"].get(3));
+    }
+
+    #[test]
+    fn load_profiles_invalid() {
+        let mut profiles = standard_profiles();
+
+        assert_eq!(
+            load_profiles(&mut profiles, "x29 profile"),
+            Err("line 1: expected x29 profile NAME PARAM:VALUE...".into())
+        );
+        assert_eq!(
+            load_profiles(&mut profiles, "\nx25 profile quiet 2:0"),
+            Err("line 2: expected x29 profile NAME PARAM:VALUE...".into())
+        );
+        assert_eq!(
+            load_profiles(&mut profiles, "x29 profile quiet 2"),
+            Err("line 1: invalid parameter 2".into())
+        );
+        assert_eq!(
+            load_profiles(&mut profiles, "x29 profile quiet 2:9"),
+            Err("line 1: invalid parameter 2:9".into())
+        );
+    }
+}